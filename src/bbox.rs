@@ -6,6 +6,8 @@ use std::{ fmt, ops };
 
 use Vec3;
 use BCube;
+use Sphere;
+use Frustum;
 
 /// Bounding box
 #[derive(Clone, Copy)]
@@ -24,7 +26,7 @@ impl ops::Sub<Vec3> for BBox {
 	type Output = BBox;
 
 	fn sub(self, other: Vec3) -> Self::Output {
-		BBox::new(self.min - other, self.max - other)
+		BBox::new_unchecked(self.min - other, self.max - other)
 	}
 }
 
@@ -32,7 +34,7 @@ impl ops::Add<Vec3> for BBox {
 	type Output = BBox;
 
 	fn add(self, other: Vec3) -> Self::Output {
-		BBox::new(self.min + other, self.max + other)
+		BBox::new_unchecked(self.min + other, self.max + other)
 	}
 }
 
@@ -48,15 +50,36 @@ impl Into<BCube> for BBox {
 }
 
 impl BBox {
-	/// Create an new `BBox` at position `p`.
+	/// Create an new `BBox` from the `min` and `max` corners.  Panics if
+	/// any component of `min` is greater than the same one in `max`.
 	pub fn new(min: Vec3, max: Vec3) -> BBox {
-		assert!(min.x <= max.x);
-		assert!(min.y <= max.y);
-		assert!(min.z <= max.z);
+		assert!(min.x <= max.x, "min.x > max.x: min {:?}, max {:?}", min, max);
+		assert!(min.y <= max.y, "min.y > max.y: min {:?}, max {:?}", min, max);
+		assert!(min.z <= max.z, "min.z > max.z: min {:?}, max {:?}", min, max);
 
 		BBox { min, max }
 	}
 
+	/// Create an new `BBox` from the `min` and `max` corners, without
+	/// checking that `min` is less than or equal `max` (except in debug
+	/// builds).  Other methods give wrong results if it isn't.
+	pub fn new_unchecked(min: Vec3, max: Vec3) -> BBox {
+		debug_assert!(min.x <= max.x && min.y <= max.y && min.z <= max.z);
+
+		BBox { min, max }
+	}
+
+	/// Create a `BBox` that covers the same space as `bcube`.
+	pub fn from_bcube(bcube: BCube) -> BBox {
+		bcube.to_bbox()
+	}
+
+	/// Get the smallest `BCube` that contains all of the `BBox`.  Same as
+	/// `BCube::from_bbox()`.
+	pub fn as_bcube(&self) -> BCube {
+		BCube::from_bbox(*self)
+	}
+
 	/// Check if `BBox` collides with `other` `BBox`.
 	pub fn collide(&self, other: BBox) -> bool {
 		   other.max.x >= self.min.x
@@ -73,8 +96,123 @@ impl BBox {
 		self.collide(BBox::new(min, max))
 	}
 
-	/// Get which sides are the farthest away from the bbox (to extend).
-	pub(crate) fn bcube_sides(&self, bcube: BCube) -> (bool, bool, bool) {
+	/// Get the point on or inside of the `BBox` that's closest to `p` (`p`
+	/// itself if it's inside).
+	pub fn closest_point(&self, p: Vec3) -> Vec3 {
+		Vec3::new(
+			p.x.max(self.min.x).min(self.max.x),
+			p.y.max(self.min.y).min(self.max.y),
+			p.z.max(self.min.z).min(self.max.z),
+		)
+	}
+
+	/// Check if `BBox` collides with `Sphere`.
+	pub fn intersects_sphere(&self, sphere: Sphere) -> bool {
+		self.closest_point(sphere.center).dist_sq(sphere.center)
+			<= sphere.radius * sphere.radius
+	}
+
+	/// Get the pair of opposite corners farthest against and along
+	/// `normal`: `(nvertex, pvertex)`.  See `BCube::pn_pair_from_normal()`.
+	pub fn pn_pair_from_normal(&self, normal: Vec3) -> (Vec3, Vec3) {
+		let (mut n, mut p) = (self.min, self.max);
+
+		if normal.x < 0.0 { ::std::mem::swap(&mut n.x, &mut p.x) }
+		if normal.y < 0.0 { ::std::mem::swap(&mut n.y, &mut p.y) }
+		if normal.z < 0.0 { ::std::mem::swap(&mut n.z, &mut p.z) }
+
+		(n, p)
+	}
+
+	/// Check if `BBox` collides with `Frustum`.
+	///
+	/// This may give false positives for boxes near the edges of the
+	/// frustum, which is fine for culling.
+	pub fn intersects_frustum(&self, frustum: Frustum) -> bool {
+		for plane in frustum.extract_planes().iter() {
+			// The corner farthest along the plane's normal.
+			let (_, p) = self.pn_pair_from_normal(plane.facing);
+
+			// If it's behind, then the whole box is.
+			if plane.distance_to_point(p) < 0.0 {
+				return false;
+			}
+		}
+
+		true
+	}
+
+	/// Split the `BBox` in two at `position` along `axis` (0 = X, 1 = Y,
+	/// 2 = Z).  Returns `None` if `position` is outside of the `BBox`.
+	pub fn split_along_axis(&self, axis: usize, position: f32)
+		-> Option<(BBox, BBox)>
+	{
+		let mut left_max = self.max;
+		let mut right_min = self.min;
+
+		{
+			let (min, max, l, r) = match axis {
+				0 => (self.min.x, self.max.x, &mut left_max.x,
+					&mut right_min.x),
+				1 => (self.min.y, self.max.y, &mut left_max.y,
+					&mut right_min.y),
+				2 => (self.min.z, self.max.z, &mut left_max.z,
+					&mut right_min.z),
+				a => panic!("axis must be 0-2, not {}", a),
+			};
+
+			if position < min || position > max {
+				return None;
+			}
+
+			*l = position;
+			*r = position;
+		}
+
+		Some((BBox::new(self.min, left_max), BBox::new(right_min, self.max)))
+	}
+
+	/// Grow each axis thinner than `min_extent` to be exactly `min_extent`
+	/// thick, keeping it centered.  Useful for flat boxes, which can give
+	/// NaNs when divided by their size.
+	pub fn pad(&self, min_extent: f32) -> BBox {
+		let grow = |min: f32, max: f32| {
+			if max - min < min_extent {
+				let mid = (min + max) / 2.0;
+				(mid - min_extent / 2.0, mid + min_extent / 2.0)
+			} else {
+				(min, max)
+			}
+		};
+		let (min_x, max_x) = grow(self.min.x, self.max.x);
+		let (min_y, max_y) = grow(self.min.y, self.max.y);
+		let (min_z, max_z) = grow(self.min.z, self.max.z);
+
+		BBox::new(Vec3::new(min_x, min_y, min_z),
+			Vec3::new(max_x, max_y, max_z))
+	}
+
+	/// Get a random point inside the `BBox`, from a uniform distribution.
+	/// Works for flat boxes, too.
+	#[cfg(feature = "rand")]
+	pub fn random_point_inside<R: ::rand::Rng>(&self, rng: &mut R) -> Vec3 {
+		// `gen_range()` panics on an empty range.
+		let mut range = |min: f32, max: f32| {
+			if min < max { rng.gen_range(min, max) } else { min }
+		};
+
+		Vec3::new(
+			range(self.min.x, self.max.x),
+			range(self.min.y, self.max.y),
+			range(self.min.z, self.max.z),
+		)
+	}
+
+	/// Get which side of `bcube` the `BBox` sticks out farther on, for each
+	/// axis (X, Y, Z): `true` for the + side, `false` for the - side.  Ties
+	/// go to the + side.  `BCube::extend()` uses this to decide which way
+	/// to grow.
+	pub fn bcube_sides(&self, bcube: BCube) -> (bool, bool, bool) {
 		let (max, min) = bcube.to_point_pair();
 		let cube = BBox::new(min, max);
 
@@ -98,6 +236,12 @@ impl BBox {
 		(p.z <= self.max.z)
 	}
 
+	/// Check if `other` is entirely inside of `BBox` (touching the sides
+	/// counts).
+	pub fn contains_bbox(&self, other: BBox) -> bool {
+		self.collide_vec3(other.min) && self.collide_vec3(other.max)
+	}
+
 	/// Get all 8 points of the `BBox`.
 	pub fn all_points(&self) -> [Vec3; 8] {
 		[
@@ -135,3 +279,146 @@ impl BBox {
 		)
 	}
 }
+
+#[test]
+fn test_bbox_intersects_sphere() {
+	let a = BBox::new(Vec3::new(-1.0, -1.0, -1.0), Vec3::new(1.0, 1.0, 1.0));
+	assert!(a.intersects_sphere(Sphere::new(Vec3::zero(), 0.5)));
+	assert!(a.intersects_sphere(Sphere::new(Vec3::zero(), 5.0)));
+	assert!(a.intersects_sphere(Sphere::new(Vec3::new(0.0, 0.0, 1.5), 0.5)));
+	assert!(!a.intersects_sphere(Sphere::new(Vec3::new(1.6, 0.0, 0.0), 0.5)));
+	assert!(!a.intersects_sphere(Sphere::new(Vec3::new(0.0, -1.6, 0.0), 0.5)));
+	// Outside the corner, but within reach of each face's plane.
+	assert!(!a.intersects_sphere(Sphere::new(Vec3::new(1.5, 1.5, 1.5), 0.8)));
+	assert!(a.intersects_sphere(Sphere::new(Vec3::new(1.5, 1.5, 1.5), 0.9)));
+}
+
+#[test]
+fn test_bbox_closest_point() {
+	let bbox = BBox::new(Vec3::new(-1.0, 0.0, 2.0), Vec3::new(3.0, 1.0, 4.0));
+	let inside = Vec3::new(0.0, 0.5, 3.0);
+	let surface = Vec3::new(3.0, 0.5, 2.0);
+
+	assert!(bbox.closest_point(inside) == inside);
+	assert!(bbox.closest_point(surface) == surface);
+	assert!(bbox.closest_point(Vec3::new(5.0, 0.5, 3.0))
+		== Vec3::new(3.0, 0.5, 3.0));
+	assert!(bbox.closest_point(Vec3::new(-9.0, 9.0, -9.0))
+		== Vec3::new(-1.0, 1.0, 2.0));
+}
+
+#[test]
+fn test_bbox_pn_pair_from_normal() {
+	let bbox = BBox::new(Vec3::new(-1.0, 0.0, 2.0), Vec3::new(3.0, 1.0, 4.0));
+	let (n, p) = bbox.pn_pair_from_normal(Vec3::new(1.0, 0.0, 0.0));
+	assert!(n.x == -1.0 && p.x == 3.0);
+	let (n, p) = bbox.pn_pair_from_normal(Vec3::new(-1.0, 2.0, -0.5));
+	assert!(n == Vec3::new(3.0, 0.0, 4.0));
+	assert!(p == Vec3::new(-1.0, 1.0, 2.0));
+}
+
+#[test]
+fn test_bbox_split_along_axis() {
+	let a = BBox::new(Vec3::new(-1.0, 0.0, 2.0), Vec3::new(3.0, 1.0, 4.0));
+	let (l, r) = a.split_along_axis(2, 2.5).unwrap();
+	assert!(l.min == a.min && r.max == a.max);
+	assert!(l.max == Vec3::new(3.0, 1.0, 2.5));
+	assert!(r.min == Vec3::new(-1.0, 0.0, 2.5));
+	let (l, r) = a.split_along_axis(0, 0.0).unwrap();
+	assert!(l.max.x == 0.0 && r.min.x == 0.0);
+	assert!(a.split_along_axis(1, 1.5).is_none());
+	assert!(a.split_along_axis(0, -1.5).is_none());
+}
+
+#[test]
+fn test_bbox_intersects_frustum() {
+	let pi = ::std::f32::consts::PI;
+	let frustum = Frustum::new(Vec3::zero(), 10.0, 0.0, 0.0, pi / 2.0,
+		pi / 2.0);
+	let bbox = |x: f32, y: f32, z: f32, h: f32| {
+		BBox::new(Vec3::new(x - h, y - h, z - h),
+			Vec3::new(x + h, y + h, z + h))
+	};
+
+	// In front, straddling the far plane, and around the whole frustum.
+	assert!(bbox(0.0, 0.0, 5.0, 1.0).intersects_frustum(frustum));
+	assert!(bbox(0.0, 0.0, 10.0, 1.0).intersects_frustum(frustum));
+	assert!(bbox(0.0, 0.0, 0.0, 50.0).intersects_frustum(frustum));
+	// Behind the near plane, past the far plane, and outside the right.
+	assert!(!bbox(0.0, 0.0, -2.0, 1.0).intersects_frustum(frustum));
+	assert!(!bbox(0.0, 0.0, 12.0, 1.0).intersects_frustum(frustum));
+	assert!(!bbox(8.0, 0.0, 5.0, 1.0).intersects_frustum(frustum));
+}
+
+#[test]
+fn test_bbox_contains_bbox() {
+	let a = BBox::new(Vec3::new(-1.0, -1.0, -1.0), Vec3::new(2.0, 2.0, 2.0));
+	let inner = BBox::new(Vec3::zero(), Vec3::one());
+	let touching = BBox::new(Vec3::new(-1.0, 0.0, 0.0),
+		Vec3::new(0.0, 2.0, 1.0));
+	let partial = BBox::new(Vec3::one(), Vec3::new(3.0, 1.5, 1.5));
+
+	assert!(a.contains_bbox(inner));
+	assert!(a.contains_bbox(touching));
+	assert!(a.contains_bbox(a));
+	assert!(!a.contains_bbox(partial));
+	assert!(!inner.contains_bbox(a));
+}
+
+#[test]
+fn test_bbox_pad() {
+	let flat = BBox::new(Vec3::new(-1.0, 2.0, 0.0), Vec3::new(3.0, 2.0, 8.0));
+	let padded = flat.pad(0.5);
+	assert!(padded.min == Vec3::new(-1.0, 1.75, 0.0));
+	assert!(padded.max == Vec3::new(3.0, 2.25, 8.0));
+	assert!(padded.center() == flat.center());
+
+	// Thin but not flat, and already thick enough.
+	let thin = BBox::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.25, 1.0, 2.0));
+	let padded = thin.pad(1.0);
+	assert!(padded.min == Vec3::new(-0.375, 0.0, 0.0));
+	assert!(padded.max == Vec3::new(0.625, 1.0, 2.0));
+	let padded = thin.pad(0.25);
+	assert!(padded.min == thin.min && padded.max == thin.max);
+}
+
+#[test]
+fn test_bbox_bcube_sides() {
+	let bcube = BCube { center: Vec3::zero(), half_len: 1.0 };
+	let bbox = BBox::new(Vec3::new(0.0, -3.0, -1.5), Vec3::new(2.0, 0.0, 1.5));
+
+	assert!(bbox.bcube_sides(bcube) == (true, false, true));
+	assert!(BBox::from_bcube(bcube).bcube_sides(bcube) == (true, true, true));
+}
+
+#[test]
+fn test_bbox_as_bcube() {
+	let bbox = BBox::new(Vec3::new(-1.0, 0.0, 2.0), Vec3::new(3.0, 1.0, 2.5));
+	let bcube = bbox.as_bcube();
+
+	assert!(bcube.to_bbox().contains_bbox(bbox));
+	// Minimal: touches both ends of the longest side.
+	assert!(bcube.half_len == 2.0);
+	assert!(bcube.center == bbox.center());
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_bbox_random_point_inside() {
+	let a = BBox::new(Vec3::new(-1.0, 2.0, 0.0), Vec3::new(3.0, 3.0, 8.0));
+	let mut rng = ::rand::thread_rng();
+
+	::test_util::assert_even_octants(a.center(), || {
+		let p = a.random_point_inside(&mut rng);
+		assert!(a.collide_vec3(p));
+		p
+	});
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_bbox_random_point_inside_flat() {
+	let flat = BBox::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 1.0, 1.0));
+	let p = flat.random_point_inside(&mut ::rand::thread_rng());
+	assert!(p.y == 1.0 && flat.collide_vec3(p));
+}
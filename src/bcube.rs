@@ -6,6 +6,8 @@ use std::fmt;
 
 use Vec3;
 use BBox;
+use Sphere;
+use Plane;
 
 /// Bounding cube
 #[derive(Clone, Copy, PartialEq)]
@@ -33,6 +35,18 @@ impl BCube {
 		BCube { center: p, half_len: 1.0 }
 	}
 
+	/// Create the smallest `BCube` that contains all of `bbox`.
+	pub fn from_bbox(bbox: BBox) -> BCube {
+		bbox.into()
+	}
+
+	/// Create the smallest `BCube` that contains all of `sphere`.  The
+	/// sphere touches the middle of each face, so its +X, +Y and +Z points
+	/// are on faces that `contains()` leaves out.
+	pub fn from_sphere(sphere: Sphere) -> BCube {
+		BCube { center: sphere.center, half_len: sphere.radius }
+	}
+
 	/// Extend the `BCube` to accommodate for `BBox`
 	pub fn extend(&mut self, p: BBox) {
 //		println!("Extend: {:?}", p);
@@ -72,6 +86,37 @@ impl BCube {
 		(p.z < z + hl)
 	}
 
+	/// Get how many times the `BCube` has to be split into octants before
+	/// a child is small enough to fit an object with half length
+	/// `object_half_len`.  Panics if `object_half_len` isn't positive, or
+	/// the `BCube`'s size isn't finite.
+	pub fn depth_estimate(self, object_half_len: f32) -> u32 {
+		assert!(object_half_len > 0.0, "object_half_len must be positive");
+		assert!(self.half_len.is_finite(), "half_len must be finite");
+
+		let mut depth = 0;
+		let mut half_len = self.half_len;
+
+		while half_len > object_half_len {
+			half_len /= 2.0;
+			depth += 1;
+		}
+
+		depth
+	}
+
+	/// Get a random point inside the `BCube`, from a uniform distribution.
+	#[cfg(feature = "rand")]
+	pub fn random_point_inside<R: ::rand::Rng>(&self, rng: &mut R) -> Vec3 {
+		let (max, min) = self.to_point_pair();
+
+		Vec3::new(
+			rng.gen_range(min.x, max.x),
+			rng.gen_range(min.y, max.y),
+			rng.gen_range(min.z, max.z),
+		)
+	}
+
 	/// Get two opposite points that are the bounds of the BCube.
 	pub fn to_point_pair(&self) -> (Vec3, Vec3) {
 		let half_cube = Vec3::new(self.half_len, self.half_len,
@@ -86,12 +131,32 @@ impl BCube {
 		BBox::new(min, max)
 	}
 
-	/// Get all 6 points or the `BCube`.
+	/// Get the smallest `Sphere` that contains all of the `BCube`.
+	pub fn to_sphere(self) -> Sphere {
+		Sphere::from_bcube(self)
+	}
+
+	/// Get the center of the `BCube`, followed by the midpoints of its 6
+	/// faces.
+	#[deprecated(note = "use `all_corners()` or `all_face_midpoints()`")]
 	pub fn all_points(&self) -> [Vec3; 7] {
+		let m = self.all_face_midpoints();
+
+		[self.center, m[0], m[1], m[2], m[3], m[4], m[5]]
+	}
+
+	/// Get the 8 corners of the `BCube`, in the same order as
+	/// `BBox::all_points()`.
+	pub fn all_corners(self) -> [Vec3; 8] {
+		self.to_bbox().all_points()
+	}
+
+	/// Get the midpoints of the 6 faces of the `BCube`, in the order +X,
+	/// +Y, +Z, -X, -Y, -Z (like `iter_faces()`).
+	pub fn all_face_midpoints(self) -> [Vec3; 6] {
 		let z = 0.0;
 
 		[
-			self.center,
 			self.center + Vec3::new(self.half_len, z, z),
 			self.center + Vec3::new(z, self.half_len, z),
 			self.center + Vec3::new(z, z, self.half_len),
@@ -101,8 +166,32 @@ impl BCube {
 		]
 	}
 
+	/// Get the 6 outward-facing planes of the faces of the `BCube`, in the
+	/// order +X, +Y, +Z, -X, -Y, -Z.
+	pub fn iter_faces(self) -> [Plane; 6] {
+		let face = |normal: Vec3| {
+			Plane::from_normal_point(normal,
+				self.center + normal * self.half_len)
+		};
+
+		[
+			face(Vec3::new(1.0, 0.0, 0.0)),
+			face(Vec3::new(0.0, 1.0, 0.0)),
+			face(Vec3::new(0.0, 0.0, 1.0)),
+			face(Vec3::new(-1.0, 0.0, 0.0)),
+			face(Vec3::new(0.0, -1.0, 0.0)),
+			face(Vec3::new(0.0, 0.0, -1.0)),
+		]
+	}
+
 	/// Get a positive and negative pair of opposite points that are the
-	/// bounds of the BCube, based around a normal.
+	/// bounds of the BCube, based around a normal: `(nvertex, pvertex)`.
+	///
+	/// `pvertex` is the corner farthest in the direction of `normal`, and
+	/// `nvertex` the corner farthest the other way.  For a plane facing
+	/// `normal`, the BCube is all in front if `nvertex` is, and all behind
+	/// if `pvertex` is.  A 0 component of `normal` counts as positive.
+	/// Both are among `all_corners()`.
 	pub fn pn_pair_from_normal(&self, normal: Vec3)
 		-> (Vec3, Vec3)
 	{
@@ -136,3 +225,134 @@ impl BCube {
 		(nvertex, pvertex)
 	}
 }
+
+#[test]
+fn test_bcube_depth_estimate() {
+	let a = BCube { center: Vec3::zero(), half_len: 8.0 };
+	assert!(a.depth_estimate(8.0) == 0);
+	assert!(a.depth_estimate(10.0) == 0);
+	assert!(a.depth_estimate(4.0) == 1);
+	assert!(a.depth_estimate(3.0) == 2);
+	assert!(a.depth_estimate(1.0) == 3);
+	assert!(a.depth_estimate(0.9) == 4);
+}
+
+#[test]
+#[should_panic(expected = "half_len must be finite")]
+fn test_bcube_depth_estimate_infinite() {
+	let a = BCube { center: Vec3::zero(), half_len: ::std::f32::INFINITY };
+	a.depth_estimate(1.0);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_bcube_random_point_inside() {
+	let a = BCube { center: Vec3::new(1.0, 2.0, -3.0), half_len: 2.0 };
+	let mut rng = ::rand::thread_rng();
+
+	::test_util::assert_even_octants(a.center, || {
+		let p = a.random_point_inside(&mut rng);
+		assert!(a.contains(p));
+		p
+	});
+}
+
+#[test]
+fn test_bcube_iter_faces() {
+	let bcube = BCube { center: Vec3::new(1.0, -2.0, 3.0), half_len: 2.0 };
+	let points = bcube.all_face_midpoints();
+
+	for face in bcube.iter_faces().iter() {
+		// Exactly one face midpoint lies on each plane.
+		let on = points.iter()
+			.filter(|p| face.distance_to_point(**p).abs() < 0.0001)
+			.count();
+		assert!(on == 1);
+		// Outward facing: the center is behind the face.
+		assert!(face.distance_to_point(bcube.center) == -2.0);
+		assert!(face.facing.mag() == 1.0);
+	}
+}
+
+#[test]
+fn test_bcube_from_sphere() {
+	let sphere = Sphere::new(Vec3::new(1.0, -2.0, 3.0), 2.5);
+	let bcube = BCube::from_sphere(sphere);
+
+	let bbox = bcube.to_bbox();
+
+	// The exact surface points on each axis are on the faces.
+	let midpoints = bcube.all_face_midpoints();
+	for (i, axis) in [Vec3::x_axis(), Vec3::y_axis(), Vec3::z_axis()]
+		.iter().enumerate()
+	{
+		let plus = sphere.center + *axis * sphere.radius;
+		let minus = sphere.center - *axis * sphere.radius;
+		assert!(plus == midpoints[i] && minus == midpoints[i + 3]);
+		assert!(bbox.collide_vec3(plus) && bbox.collide_vec3(minus));
+		// `contains()` includes the - faces, but not the + faces.
+		assert!(bcube.contains(minus) && !bcube.contains(plus));
+	}
+
+	// Points all around the surface of the sphere.
+	for i in 0..16 {
+		for j in 0..=8 {
+			let a = i as f32 * ::std::f32::consts::PI / 8.0;
+			let b = j as f32 * ::std::f32::consts::PI / 8.0;
+			let p = sphere.center + Vec3::new(a.cos() * b.sin(),
+				b.cos(), a.sin() * b.sin()) * sphere.radius;
+			assert!(bbox.collide_vec3(p));
+		}
+	}
+}
+
+#[test]
+fn test_bcube_from_bbox() {
+	for &(center, half_len) in [(Vec3::zero(), 1.0),
+		(Vec3::new(1.0, -2.0, 3.0), 0.25),
+		(Vec3::new(-100.0, 50.0, 0.5), 40.0)].iter()
+	{
+		let bcube = BCube { center, half_len };
+		let bbox = BBox::from_bcube(bcube);
+		assert!(BCube::from_bbox(bbox).half_len >= bcube.half_len);
+		assert!(BCube::from_bbox(bbox).center == center);
+	}
+
+	let bbox = BBox::new(Vec3::new(-1.0, 0.0, 2.0), Vec3::new(3.0, 1.0, 2.5));
+	let bcube = BCube::from_bbox(bbox);
+	assert!(bcube.half_len == 2.0);
+	assert!(bcube.to_bbox().contains_bbox(bbox));
+}
+
+#[test]
+fn test_bcube_pn_pair_from_normal() {
+	let bcube = BCube { center: Vec3::new(1.0, -2.0, 3.0), half_len: 2.0 };
+	let (n, p) = bcube.pn_pair_from_normal(Vec3::new(1.0, 0.0, 0.0));
+	assert!(n.x == -1.0 && p.x == 3.0);
+	let (n, p) = bcube.pn_pair_from_normal(Vec3::new(1.0, -1.0, -0.5));
+	assert!(n == Vec3::new(-1.0, 0.0, 5.0));
+	assert!(p == Vec3::new(3.0, -4.0, 1.0));
+}
+
+#[test]
+fn test_bcube_all_corners() {
+	let bcube = BCube { center: Vec3::new(1.0, -2.0, 3.0), half_len: 2.0 };
+	let corners = bcube.all_corners();
+	let (max, min) = bcube.to_point_pair();
+
+	// Every combination of min and max, once each.
+	for i in 0..8 {
+		let x = if i & 4 != 0 { max.x } else { min.x };
+		let y = if i & 2 != 0 { max.y } else { min.y };
+		let z = if i & 1 != 0 { max.z } else { min.z };
+		let corner = Vec3::new(x, y, z);
+		assert!(corners.iter().filter(|c| **c == corner).count() == 1);
+	}
+
+	let midpoints = bcube.all_face_midpoints();
+	assert!(midpoints[0] == Vec3::new(3.0, -2.0, 3.0));
+	assert!(midpoints[5] == Vec3::new(1.0, -2.0, 1.0));
+	for (face, midpoint) in bcube.iter_faces().iter().zip(midpoints.iter()) {
+		assert!(face.distance_to_point(*midpoint) == 0.0);
+	}
+}
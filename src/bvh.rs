@@ -0,0 +1,313 @@
+// "ami" crate - Licensed under the MIT LICENSE
+//  * Copyright (c) 2017-2018  Jeron A. Lau <jeron.lau@plopgrizzly.com>
+
+use Vec3;
+use BBox;
+use Ray;
+
+/// Most items to keep in a leaf node.
+const LEAF_SIZE: usize = 4;
+
+/// A bounding volume hierarchy: a binary tree of `BBox`es that adapts to how
+/// the items are spread out.
+///
+/// Nodes are stored in a vector, with the root at index 0.  The items under
+/// each node are kept next to each other in the item vector.
+pub struct BVH<T> {
+	items: Vec<(BBox, T)>,
+	nodes: Vec<Node>,
+}
+
+/// A node is either a branch with two children or a leaf of items.
+enum Node {
+	Branch { bbox: BBox, left: usize, right: usize },
+	Leaf { bbox: BBox, start: usize, end: usize },
+}
+
+impl Node {
+	fn bbox(&self) -> BBox {
+		match *self {
+			Node::Branch { bbox, .. } => bbox,
+			Node::Leaf { bbox, .. } => bbox,
+		}
+	}
+}
+
+/// Get the smallest `BBox` containing both `a` and `b`.
+fn union(a: BBox, b: BBox) -> BBox {
+	BBox::new_unchecked(
+		Vec3::new(a.min.x.min(b.min.x), a.min.y.min(b.min.y),
+			a.min.z.min(b.min.z)),
+		Vec3::new(a.max.x.max(b.max.x), a.max.y.max(b.max.y),
+			a.max.z.max(b.max.z)),
+	)
+}
+
+/// Get the surface area of a `BBox`.
+fn surface_area(bbox: BBox) -> f32 {
+	let d = bbox.max - bbox.min;
+
+	2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+}
+
+/// Get the component of `v` along `axis` (0 = X, 1 = Y, 2 = Z).
+fn axis_of(v: Vec3, axis: usize) -> f32 {
+	match axis {
+		0 => v.x,
+		1 => v.y,
+		_ => v.z,
+	}
+}
+
+impl<T> BVH<T> {
+	/// Build a `BVH` from items and their bounding boxes, splitting nodes
+	/// top-down with the surface area heuristic (SAH).
+	pub fn build_from_iter<I>(items: I) -> BVH<T>
+		where I: Iterator<Item = (BBox, T)>
+	{
+		let mut bvh = BVH { items: items.collect(), nodes: vec![] };
+
+		if !bvh.items.is_empty() {
+			let end = bvh.items.len();
+			bvh.build(0, end);
+		}
+
+		bvh
+	}
+
+	/// Build the node for items `start..end`, returning its index.
+	fn build(&mut self, start: usize, end: usize) -> usize {
+		let bbox = self.items[start + 1..end].iter()
+			.fold(self.items[start].0, |a, b| union(a, b.0));
+		let index = self.nodes.len();
+
+		self.nodes.push(Node::Leaf { bbox, start, end });
+
+		if end - start <= LEAF_SIZE {
+			return index;
+		}
+
+		let n = end - start;
+
+		// Bounds of the item centers, to find the axis they spread out
+		// along the most.
+		let first = self.items[start].0.center();
+		let (lo, hi) = self.items[start..end].iter().fold((first, first),
+			|(lo, hi), item| {
+				let c = item.0.center();
+				(Vec3::new(lo.x.min(c.x), lo.y.min(c.y), lo.z.min(c.z)),
+				 Vec3::new(hi.x.max(c.x), hi.y.max(c.y), hi.z.max(c.z)))
+			});
+		let spread = hi - lo;
+		let widest = spread.dominant_axis();
+
+		// All centers are the same, so every split costs the same: split
+		// down the middle to keep the tree balanced.
+		if !(axis_of(spread, widest) > 0.0) {
+			return self.split_median(index, bbox, widest, start, end);
+		}
+
+		// Find the split with the lowest cost.  The cost of each side is
+		// its surface area times its number of items.
+		let mut best = (::std::f32::INFINITY, 0, 0);
+		for axis in 0..3 {
+			self.sort_along(axis, start, end);
+
+			// Surface areas of items `start..start + i + 1` (left)
+			let mut left = vec![0.0; n];
+			let mut acc = self.items[start].0;
+			for i in 0..n {
+				acc = union(acc, self.items[start + i].0);
+				left[i] = surface_area(acc);
+			}
+
+			// Try splitting after each item, from the right.
+			let mut acc = self.items[end - 1].0;
+			for i in (1..n).rev() {
+				acc = union(acc, self.items[start + i].0);
+				let cost = left[i - 1] * i as f32
+					+ surface_area(acc) * (n - i) as f32;
+				if cost < best.0 {
+					best = (cost, axis, i);
+				}
+			}
+		}
+
+		// The costs overflowed (huge boxes): fall back to the middle.
+		if !best.0.is_finite() {
+			return self.split_median(index, bbox, widest, start, end);
+		}
+
+		// Don't split if it's no better than testing every item.
+		if best.0 >= surface_area(bbox) * n as f32 && n <= LEAF_SIZE * 4 {
+			return index;
+		}
+
+		let (_, axis, split) = best;
+		self.sort_along(axis, start, end);
+		self.split_at(index, bbox, start, start + split, end)
+	}
+
+	/// Split node `index` in half along `axis`, returning `index`.
+	fn split_median(&mut self, index: usize, bbox: BBox, axis: usize,
+		start: usize, end: usize) -> usize
+	{
+		self.sort_along(axis, start, end);
+		self.split_at(index, bbox, start, start + (end - start) / 2, end)
+	}
+
+	/// Make node `index` a branch of items `start..split` and
+	/// `split..end`, returning `index`.
+	fn split_at(&mut self, index: usize, bbox: BBox, start: usize,
+		split: usize, end: usize) -> usize
+	{
+		let left = self.build(start, split);
+		let right = self.build(split, end);
+		self.nodes[index] = Node::Branch { bbox, left, right };
+
+		index
+	}
+
+	/// Sort items `start..end` by their centers along `axis`.
+	fn sort_along(&mut self, axis: usize, start: usize, end: usize) {
+		self.items[start..end].sort_by(|a, b| {
+			let a = axis_of(a.0.center(), axis);
+			let b = axis_of(b.0.center(), axis);
+			a.partial_cmp(&b).unwrap_or(::std::cmp::Ordering::Equal)
+		});
+	}
+
+	/// Get all items whose `BBox` the ray hits, with the distance along
+	/// the ray to where it enters the `BBox` (0 if it starts inside).
+	/// Items are sorted nearest first.
+	pub fn query_ray<'a>(&'a self, ray: Ray)
+		-> impl Iterator<Item = (&'a T, f32)>
+	{
+		let mut found = vec![];
+
+		self.query(&|bbox| ray.intersects_bbox(bbox).is_some(),
+			&mut |bbox, item| if let Some((t, _)) =
+				ray.intersects_bbox(bbox)
+			{
+				found.push((item, t));
+			});
+
+		found.sort_by(|a, b| a.1.partial_cmp(&b.1)
+			.unwrap_or(::std::cmp::Ordering::Equal));
+		found.into_iter()
+	}
+
+	/// Get all items whose `BBox` collides with `bbox`.
+	pub fn query_range<'a>(&'a self, bbox: BBox)
+		-> impl Iterator<Item = &'a T>
+	{
+		let mut found = vec![];
+
+		self.query(&|b| b.collide(bbox), &mut |b, item| if b.collide(bbox) {
+			found.push(item);
+		});
+
+		found.into_iter()
+	}
+
+	/// Visit every item in nodes for which `test` passes.
+	fn query<'a, F, G>(&'a self, test: &F, visit: &mut G)
+		where F: Fn(BBox) -> bool, G: FnMut(BBox, &'a T)
+	{
+		let mut stack = if self.nodes.is_empty() { vec![] } else { vec![0] };
+
+		while let Some(i) = stack.pop() {
+			let node = &self.nodes[i];
+			if !test(node.bbox()) {
+				continue;
+			}
+
+			match *node {
+				Node::Branch { left, right, .. } => {
+					stack.push(right);
+					stack.push(left);
+				}
+				Node::Leaf { start, end, .. } => {
+					for &(bbox, ref item) in &self.items[start..end] {
+						visit(bbox, item);
+					}
+				}
+			}
+		}
+	}
+}
+
+#[test]
+fn test_bvh_query() {
+	let mut rng = ::test_util::Lcg::new(0x12345678);
+	let mut rand = || rng.next_f32();
+
+	let mut boxes = vec![];
+	for i in 0..500 {
+		let p = Vec3::new(rand(), rand() * 0.1, rand());
+		boxes.push((BBox::new(p, p + Vec3::new(1.0, 0.5, 2.0)), i));
+	}
+	let bvh = BVH::build_from_iter(boxes.clone().into_iter());
+
+	for _ in 0..20 {
+		let p = Vec3::new(rand(), rand() * 0.1, rand());
+		let range = BBox::new(p, p + 10.0);
+		let mut expected: Vec<i32> = boxes.iter()
+			.filter(|b| b.0.collide(range))
+			.map(|b| b.1)
+			.collect();
+		let mut found: Vec<i32> = bvh.query_range(range).cloned()
+			.collect();
+		expected.sort();
+		found.sort();
+		assert!(found == expected);
+
+		let ray = Ray::new(p, Vec3::new(rand(), rand() * 0.1, rand()));
+		let expected = boxes.iter()
+			.filter(|b| ray.intersects_bbox(b.0).is_some())
+			.count();
+		let found: Vec<(&i32, f32)> = bvh.query_ray(ray).collect();
+		assert!(found.len() == expected);
+		assert!(found.windows(2).all(|w| w[0].1 <= w[1].1));
+	}
+}
+
+#[cfg(test)]
+impl<T> BVH<T> {
+	/// Get how many levels deep the tree is.
+	fn depth(&self, index: usize) -> usize {
+		match self.nodes[index] {
+			Node::Branch { left, right, .. } => {
+				1 + self.depth(left).max(self.depth(right))
+			}
+			Node::Leaf { .. } => 1,
+		}
+	}
+}
+
+#[test]
+fn test_bvh_identical_boxes() {
+	let p = Vec3::new(1.0, 2.0, 3.0);
+	let bvh = BVH::build_from_iter((0..20000).map(|i| (BBox::new(p, p), i)));
+
+	// Balanced, not one item peeled off per level.
+	assert!(bvh.depth(0) < 20);
+	assert!(bvh.query_range(BBox::new(p, p)).count() == 20000);
+	assert!(bvh.query_range(BBox::new(p + 1.0, p + 2.0)).count() == 0);
+}
+
+#[test]
+fn test_bvh_huge_boxes() {
+	let max = ::std::f32::MAX;
+	let bvh = BVH::build_from_iter((0..100).map(|i| {
+		let x = i as f32;
+		(BBox::new(Vec3::new(-max, x, -max), Vec3::new(max, x + 1.0, max)),
+			i)
+	}));
+
+	let range = BBox::new(Vec3::new(0.0, 10.5, 0.0),
+		Vec3::new(1.0, 12.5, 1.0));
+	let mut found: Vec<i32> = bvh.query_range(range).cloned().collect();
+	found.sort();
+	assert!(found == vec![10, 11, 12]);
+}
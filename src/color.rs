@@ -0,0 +1,110 @@
+// "ami" crate - Licensed under the MIT LICENSE
+//  * Copyright (c) 2017-2018  Jeron A. Lau <jeron.lau@plopgrizzly.com>
+
+use std::fmt;
+
+use Vec4;
+
+/// An RGBA color, each channel ranging from 0 to 1.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Color {
+	/// Red channel
+	pub r: f32,
+	/// Green channel
+	pub g: f32,
+	/// Blue channel
+	pub b: f32,
+	/// Alpha channel (0 is transparent, 1 is opaque)
+	pub a: f32,
+}
+
+impl fmt::Debug for Color {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "rgba({},{},{},{})", self.r, self.g, self.b, self.a)
+	}
+}
+
+impl Color {
+	/// Opaque white
+	pub const WHITE: Color = Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+	/// Opaque black
+	pub const BLACK: Color = Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 };
+	/// Fully transparent black
+	pub const TRANSPARENT: Color = Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 };
+
+	/// Create a new Color
+	pub fn new(r: f32, g: f32, b: f32, a: f32) -> Color {
+		Color { r, g, b, a }
+	}
+
+	/// Create a Color from a Vec4 (x, y, z, w) = (r, g, b, a)
+	pub fn from_vec4(v: Vec4) -> Color {
+		Color::new(v.x, v.y, v.z, v.w)
+	}
+
+	/// Convert to a Vec4 (x, y, z, w) = (r, g, b, a)
+	pub fn to_vec4(self) -> Vec4 {
+		Vec4::new(self.r, self.g, self.b, self.a)
+	}
+
+	/// Create a Color from a hex value in the form `0xRRGGBBAA`.
+	pub fn from_u32(hex: u32) -> Color {
+		let channel = |shift: u32| ((hex >> shift) & 0xFF) as f32 / 255.0;
+
+		Color::new(channel(24), channel(16), channel(8), channel(0))
+	}
+
+	/// Convert to a hex value in the form `0xRRGGBBAA`.  Channels are
+	/// clamped to 0-1.
+	pub fn to_u32(self) -> u32 {
+		let channel = |c: f32, shift: u32| {
+			((c.max(0.0).min(1.0) * 255.0).round() as u32) << shift
+		};
+
+		channel(self.r, 24) | channel(self.g, 16) | channel(self.b, 8)
+			| channel(self.a, 0)
+	}
+
+	/// Linear interpolation between `self` (t = 0) and `other` (t = 1).
+	pub fn lerp(self, other: Color, t: f32) -> Color {
+		Color::new(
+			self.r + (other.r - self.r) * t,
+			self.g + (other.g - self.g) * t,
+			self.b + (other.b - self.b) * t,
+			self.a + (other.a - self.a) * t,
+		)
+	}
+
+	/// Multiply the color channels by alpha.
+	pub fn premultiply_alpha(self) -> Color {
+		Color::new(self.r * self.a, self.g * self.a, self.b * self.a,
+			self.a)
+	}
+}
+
+impl From<Vec4> for Color {
+	fn from(v: Vec4) -> Color {
+		Color::from_vec4(v)
+	}
+}
+
+impl From<Color> for Vec4 {
+	fn from(c: Color) -> Vec4 {
+		c.to_vec4()
+	}
+}
+
+#[test]
+fn test_color() {
+	let c = Color::from_u32(0xFF8000CC);
+	assert!(c.r == 1.0 && c.g == 128.0 / 255.0 && c.b == 0.0);
+	assert!(c.to_u32() == 0xFF8000CC);
+	assert!(Color::WHITE.to_u32() == 0xFFFFFFFF);
+	assert!(Color::TRANSPARENT.to_u32() == 0);
+	assert!(Color::from_vec4(c.to_vec4()) == c);
+
+	assert!(Color::BLACK.lerp(Color::WHITE, 0.5)
+		== Color::new(0.5, 0.5, 0.5, 1.0));
+	assert!(Color::new(1.0, 0.5, 0.25, 0.5).premultiply_alpha()
+		== Color::new(0.5, 0.25, 0.125, 0.5));
+}
@@ -6,15 +6,25 @@ use std::fmt;
 use Vec3;
 use BCube;
 use BBox;
-// use math::Plane;
+use Plane;
+use Sphere;
+use Mat4;
+use Vec4;
+use triple_plane_intersection;
 
 #[derive(Clone, Copy, PartialEq)]
 /// A Frustum
+///
+/// Since 0.10 there is a `near` field, so code that builds a `Frustum` with
+/// a struct literal must set it too (`near: 0.0` keeps the old behavior), or
+/// use `Frustum::new()`.
 pub struct Frustum {
 	/// The center of the frustum
 	pub center: Vec3,
 	/// The radius of the frustum
 	pub radius: f32,
+	/// How far the near plane is in front of `center` (0 from `new()`)
+	pub near: f32,
 	/// The fov in x
 	pub wfov: f32,
 	/// the fov in y
@@ -27,12 +37,20 @@ pub struct Frustum {
 
 impl fmt::Debug for Frustum {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "(radius: {:?})", self.radius)
+		let [left, right, bottom, top, near, far] = self.extract_planes();
+
+		write!(f, "center: {:?} radius: {:?} near: {:?} fov: {:?}°×{:?}° \
+			rot: {:?}°,{:?}°", self.center, self.radius, self.near,
+			self.wfov.to_degrees(), self.hfov.to_degrees(),
+			self.xrot.to_degrees(), self.yrot.to_degrees())?;
+		write!(f, " (left: {:?} right: {:?} bottom: {:?} top: {:?} \
+			near: {:?} far: {:?})", left.facing, right.facing,
+			bottom.facing, top.facing, near.facing, far.facing)
 	}
 }
 
 impl Frustum {
-	/// Create a new viewing frustum.
+	/// Create a new viewing frustum, with the near plane at `center`.
 	///
 	/// * `center` - The center of the frustum cone.
 	/// * `radius` - How far can you see?
@@ -64,7 +82,157 @@ impl Frustum {
 
 		Frustum { near, far, top, bottom, right, left }*/
 
-		Frustum { center, radius, xrot, yrot, wfov, hfov }
+		Frustum { center, radius, near: 0.0, xrot, yrot, wfov, hfov }
+	}
+
+	/// Get the viewing frustum of a combined view-projection matrix (the
+	/// view applied first), using Gribb-Hartmann plane extraction.  Depth
+	/// must map to 0 to 1 in clip space, like `Mat4::perspective_lh()`.
+	///
+	/// A `Frustum` can't be rolled or off-center, so those are lost.
+	/// Panics if `vp` isn't a perspective projection.
+	pub fn from_view_projection(vp: Mat4) -> Frustum {
+		// Clip space `a + b * sign` ≥ 0 is the inside of the plane.
+		let plane = |a: Vec4, b: Vec4, sign: f32| {
+			let n = Vec3::new(a.x + b.x * sign, a.y + b.y * sign,
+				a.z + b.z * sign);
+			let mag = n.mag();
+
+			Plane { facing: n / mag, offset: -(a.w + b.w * sign) / mag }
+		};
+		let (x, y, z, w) = (vp.row(0), vp.row(1), vp.row(2), vp.row(3));
+		let left = plane(w, x, 1.0);
+		let right = plane(w, x, -1.0);
+		let bottom = plane(w, y, 1.0);
+		let top = plane(w, y, -1.0);
+		let near = plane(z, z, 0.0);
+		let far = plane(w, z, -1.0);
+
+		// The sides all meet at the camera.
+		let center = triple_plane_intersection(left, right, bottom)
+			.expect("Not a perspective projection");
+		let forward = near.facing;
+		// Each side's facing is `sin(fov / 2)` along forward.
+		let half_sin = |a: Plane, b: Plane| {
+			(a.facing.dot(forward) + b.facing.dot(forward)) / 2.0
+		};
+		let wfov = half_sin(left, right).asin() * 2.0;
+		let hfov = half_sin(bottom, top).asin() * 2.0;
+		// Inverse of `basis()`.
+		let xrot = forward.x.atan2(forward.z);
+		let yrot = forward.y.asin();
+
+		Frustum {
+			center,
+			radius: far.distance_to_point(center),
+			near: -near.distance_to_point(center),
+			wfov, hfov, xrot, yrot,
+		}
+	}
+
+	/// Get the right, up and forward directions of the frustum.
+	fn basis(&self) -> (Vec3, Vec3, Vec3) {
+		let (sx, cx) = (self.xrot.sin(), self.xrot.cos());
+		let (sy, cy) = (self.yrot.sin(), self.yrot.cos());
+
+		let right = Vec3::new(cx, 0.0, -sx);
+		let up = Vec3::new(-sx * sy, cy, -cx * sy);
+		let forward = Vec3::new(sx * cy, sy, cx * cy);
+
+		(right, up, forward)
+	}
+
+	/// Get the 6 planes that bound the frustum, in the order left, right,
+	/// bottom, top, near, far.  All planes face inward, so a point is
+	/// inside the frustum if it's on the positive side of all of them.
+	///
+	/// The frustum faces +Z when not rotated.  The near plane is `near`
+	/// away from `center` and the far plane is `radius` away from it.
+	pub fn extract_planes(&self) -> [Plane; 6] {
+		let (right, up, forward) = self.basis();
+		let (sw, cw) = ((self.wfov / 2.0).sin(), (self.wfov / 2.0).cos());
+		let (sh, ch) = ((self.hfov / 2.0).sin(), (self.hfov / 2.0).cos());
+		let apex = forward.dot(self.center);
+
+		let side = |dir: Vec3| {
+			let facing = dir.normalize();
+			Plane { facing, offset: facing.dot(self.center) }
+		};
+
+		[
+			side(right * cw + forward * sw),
+			side(-right * cw + forward * sw),
+			side(up * ch + forward * sh),
+			side(-up * ch + forward * sh),
+			Plane::new(forward, apex + self.near),
+			Plane::new(-forward, -(apex + self.radius)),
+		]
+	}
+
+	/// Get the 8 corners of the frustum, near corners first.
+	pub fn corners(&self) -> [Vec3; 8] {
+		let (right, up, forward) = self.basis();
+		let w = (self.wfov / 2.0).tan();
+		let h = (self.hfov / 2.0).tan();
+		let corner = |dist: f32, x: f32, y: f32| {
+			self.center + (forward + right * (x * w) + up * (y * h)) * dist
+		};
+
+		[
+			corner(self.near, -1.0, -1.0), corner(self.near, 1.0, -1.0),
+			corner(self.near, -1.0, 1.0), corner(self.near, 1.0, 1.0),
+			corner(self.radius, -1.0, -1.0),
+			corner(self.radius, 1.0, -1.0),
+			corner(self.radius, -1.0, 1.0),
+			corner(self.radius, 1.0, 1.0),
+		]
+	}
+
+	/// Check if `point` is inside the frustum (on the positive side of all
+	/// 6 planes, including on them).  Unlike `collide_point()`, this
+	/// checks the sides too.
+	pub fn contains_point(&self, point: Vec3) -> bool {
+		self.extract_planes().iter()
+			.all(|plane| plane.distance_to_point(point) >= 0.0)
+	}
+
+	/// Split the frustum into slices along its depth, for cascaded shadow
+	/// maps.  `splits` are sorted fractions between 0 and 1 of the way from
+	/// the near plane to the far plane, where one slice ends and the next
+	/// begins.
+	pub fn split(self, splits: &[f32]) -> Vec<Frustum> {
+		let depth = self.radius - self.near;
+		let mut slices = Vec::with_capacity(splits.len() + 1);
+		let mut near = self.near;
+
+		for split in splits {
+			let far = self.near + depth * split;
+			slices.push(Frustum { near, radius: far, ..self });
+			near = far;
+		}
+		slices.push(Frustum { near, ..self });
+
+		slices
+	}
+
+	/// Get the distance from `point` to the center of the frustum.
+	pub fn distance_to_center(&self, point: Vec3) -> f32 {
+		(point - self.center).mag()
+	}
+
+	/// Get a `Sphere` that contains the whole frustum.
+	pub fn bounding_sphere(&self) -> Sphere {
+		let w = (self.wfov / 2.0).tan();
+		let h = (self.hfov / 2.0).tan();
+
+		// Far corners are the farthest from the center.
+		Sphere::new(self.center, self.radius * (1.0 + w * w + h * h).sqrt())
+	}
+
+	/// If viewing frustum collides with the sphere.  This may give false
+	/// positives for spheres near the edges of the frustum.
+	pub fn intersects_sphere(&self, sphere: Sphere) -> bool {
+		sphere.intersects_frustum(*self)
 	}
 
 	/// 
@@ -80,7 +248,11 @@ impl Frustum {
 
 	/// If viewing frustum collides with the bounding box.
 	pub fn collide_bcube(&self, bcube: BCube) -> bool {
-		for i in bcube.all_points().iter() {
+		if (bcube.center - self.center).mag() <= self.radius {
+			return true;
+		}
+
+		for i in bcube.all_face_midpoints().iter() {
 			if (*i - self.center).mag() <= self.radius {
 				return true;
 			}
@@ -124,3 +296,149 @@ impl Frustum {
 			&& self.bottom.isdistpos_point(point)*/
 	}
 }
+
+#[test]
+fn test_frustum_planes() {
+	let pi = ::std::f32::consts::PI;
+	let inside = |frustum: Frustum, p: Vec3| {
+		frustum.extract_planes().iter().all(|pl| pl.isdistpos_point(p))
+	};
+
+	let a = Frustum::new(Vec3::zero(), 10.0, 0.0, 0.0, pi / 2.0, pi / 2.0);
+	assert!(inside(a, Vec3::new(0.0, 0.0, 5.0)));
+	assert!(inside(a, Vec3::new(4.0, -4.0, 5.0)));
+	assert!(!inside(a, Vec3::new(6.0, 0.0, 5.0)));
+	assert!(!inside(a, Vec3::new(0.0, 6.0, 5.0)));
+	assert!(!inside(a, Vec3::new(0.0, 0.0, -1.0)));
+	assert!(!inside(a, Vec3::new(0.0, 0.0, 11.0)));
+
+	// Turned to face +X
+	let b = Frustum::new(Vec3::new(1.0, 0.0, 0.0), 10.0, pi / 2.0, 0.0,
+		pi / 2.0, pi / 2.0);
+	assert!(inside(b, Vec3::new(6.0, 0.0, 0.0)));
+	assert!(!inside(b, Vec3::new(0.0, 0.0, 5.0)));
+}
+
+#[test]
+fn test_frustum_bounding_sphere() {
+	let a = Frustum::new(Vec3::new(1.0, 2.0, 3.0), 10.0, 0.3, -0.2, 1.2, 0.9);
+	let sphere = a.bounding_sphere();
+
+	for corner in a.corners().iter() {
+		assert!(sphere.center.dist(*corner) <= sphere.radius + 0.0001);
+	}
+	assert!(a.distance_to_center(Vec3::new(1.0, 2.0, -1.0)) == 4.0);
+}
+
+#[test]
+fn test_frustum_intersects_sphere() {
+	let pi = ::std::f32::consts::PI;
+	let a = Frustum::new(Vec3::zero(), 10.0, 0.0, 0.0, pi / 2.0, pi / 2.0);
+	let sphere = |x: f32, y: f32, z: f32| Sphere::new(Vec3::new(x, y, z), 1.0);
+
+	assert!(a.intersects_sphere(sphere(0.0, 0.0, 5.0)));
+	// Straddling the left, bottom and far planes
+	assert!(a.intersects_sphere(sphere(-5.5, 0.0, 5.0)));
+	assert!(a.intersects_sphere(sphere(0.0, -5.5, 5.0)));
+	assert!(a.intersects_sphere(sphere(0.0, 0.0, 10.5)));
+	// Outside of each plane
+	assert!(!a.intersects_sphere(sphere(-7.0, 0.0, 5.0)));
+	assert!(!a.intersects_sphere(sphere(7.0, 0.0, 5.0)));
+	assert!(!a.intersects_sphere(sphere(0.0, -7.0, 5.0)));
+	assert!(!a.intersects_sphere(sphere(0.0, 7.0, 5.0)));
+	assert!(!a.intersects_sphere(sphere(0.0, 0.0, -1.5)));
+	assert!(!a.intersects_sphere(sphere(0.0, 0.0, 11.5)));
+}
+
+#[test]
+fn test_frustum_split() {
+	let inside = |frustum: &Frustum, p: Vec3| {
+		frustum.extract_planes().iter().all(|pl| pl.isdistpos_point(p))
+	};
+	let mut a = Frustum::new(Vec3::new(1.0, 2.0, 3.0), 100.0, 0.3, -0.2,
+		1.2, 0.9);
+	a.near = 1.0;
+
+	let slices = a.split(&[0.1, 0.3, 0.6]);
+	assert!(slices.len() == 4);
+	assert!(slices[0].near == 1.0 && slices[3].radius == 100.0);
+	for pair in slices.windows(2) {
+		assert!(pair[0].radius == pair[1].near);
+		assert!(pair[0].corners()[4] == pair[1].corners()[0]);
+	}
+
+	// Points in the frustum are in a slice, and points outside aren't.
+	let (mut x, mut y, mut z) = (0.0f32, 0.0f32, 0.0f32);
+	let mut count = 0;
+	for _ in 0..1000 {
+		x = (x + 0.618034).fract();
+		y = (y + 0.754878).fract();
+		z = (z + 0.569840).fract();
+		let p = a.center + Vec3::new(x - 0.5, y - 0.5, z) * 120.0;
+		assert!(inside(&a, p) == slices.iter().any(|s| inside(s, p)));
+		if inside(&a, p) { count += 1 }
+	}
+	assert!(count > 100);
+}
+
+#[test]
+fn test_frustum_contains_point() {
+	let pi = ::std::f32::consts::PI;
+	let mut a = Frustum::new(Vec3::zero(), 10.0, 0.0, 0.0, pi / 2.0,
+		pi / 2.0);
+	a.near = 1.0;
+
+	assert!(a.contains_point(Vec3::new(0.0, 0.0, 5.0)));
+	// Just beyond each plane
+	assert!(!a.contains_point(Vec3::new(-5.1, 0.0, 5.0)));
+	assert!(!a.contains_point(Vec3::new(5.1, 0.0, 5.0)));
+	assert!(!a.contains_point(Vec3::new(0.0, -5.1, 5.0)));
+	assert!(!a.contains_point(Vec3::new(0.0, 5.1, 5.0)));
+	assert!(!a.contains_point(Vec3::new(0.0, 0.0, 0.9)));
+	assert!(!a.contains_point(Vec3::new(0.0, 0.0, 10.1)));
+	// On the boundary
+	assert!(a.contains_point(Vec3::new(0.0, 0.0, 1.0)));
+	assert!(a.contains_point(Vec3::new(0.0, 0.0, 10.0)));
+	assert!(a.contains_point(Vec3::new(0.0, 4.0, 4.0)));
+}
+
+#[test]
+fn test_frustum_from_view_projection() {
+	let pi = ::std::f32::consts::PI;
+	let proj = Mat4::perspective_lh(pi / 3.0, 1.5, 0.5, 20.0);
+	let f = Frustum::from_view_projection(proj);
+	assert!(f.center.mag() < 0.0001);
+	assert!((f.near - 0.5).abs() < 0.0001);
+	assert!((f.radius - 20.0).abs() < 0.001);
+	assert!((f.hfov - pi / 3.0).abs() < 0.0001);
+	let wfov = 2.0 * ((pi / 6.0).tan() * 1.5).atan();
+	assert!((f.wfov - wfov).abs() < 0.0001);
+	assert!(f.xrot.abs() < 0.0001 && f.yrot.abs() < 0.0001);
+
+	// Camera at (1, 2, 3), turned to the right and up.
+	let eye = Vec3::new(1.0, 2.0, 3.0);
+	let rotated = Frustum::new(eye, 20.0, 0.4, 0.2, wfov, pi / 3.0);
+	let (right, up, forward) = rotated.basis();
+	let view = Mat4::look_at_lh(eye, eye + forward, up);
+	let f = Frustum::from_view_projection(view * proj);
+	assert!(f.center.dist(eye) < 0.0001);
+	assert!((f.xrot - 0.4).abs() < 0.0001 && (f.yrot - 0.2).abs() < 0.0001);
+
+	// Planes worked out by hand: the sides are tilted `fov / 2` in from
+	// forward around the camera.
+	let (sw, cw) = ((wfov / 2.0).sin(), (wfov / 2.0).cos());
+	let (sh, ch) = ((pi / 6.0).sin(), (pi / 6.0).cos());
+	let through_eye = |n: Vec3| Plane::from_normal_point(n, eye);
+	let expected = [
+		through_eye(right * cw + forward * sw),
+		through_eye(-right * cw + forward * sw),
+		through_eye(up * ch + forward * sh),
+		through_eye(-up * ch + forward * sh),
+		Plane::from_normal_point(forward, eye + forward * 0.5),
+		Plane::from_normal_point(-forward, eye + forward * 20.0),
+	];
+	for (a, b) in f.extract_planes().iter().zip(expected.iter()) {
+		assert!(a.facing.dist(b.facing) < 0.0001);
+		assert!((a.offset - b.offset).abs() < 0.001);
+	}
+}
@@ -12,28 +12,49 @@
 	html_root_url = "http://plopgrizzly.com/ami/"
 )]
 
+#[cfg(feature = "rand")]
+extern crate rand;
+
+mod mat3;
 mod mat4;
 mod bbox;
 mod bcube;
 mod frustum;
 mod octree;
+mod bvh;
 mod plane;
+mod quaternion;
+mod ray;
+mod segment;
+mod triangle;
+mod sphere;
 mod collider;
 mod vec2;
 mod vec3;
 mod vec4;
+mod color;
 mod parent;
+#[cfg(test)]
+mod test_util;
 
+pub use mat3::*;
 pub use mat4::*;
 pub use bcube::*;
 pub use bbox::*;
 pub use frustum::*;
 pub use octree::{ Octree, Id };
+pub use bvh::*;
 pub use plane::*;
+pub use quaternion::*;
+pub use ray::*;
+pub use segment::*;
+pub use triangle::*;
+pub use sphere::*;
 pub use collider::*;
 pub use vec2::*;
 pub use vec3::*;
 pub use vec4::*;
+pub use color::*;
 pub use parent::*;
 
 /// Cast a constant pointer to another type.
@@ -0,0 +1,82 @@
+// "ami" crate - Licensed under the MIT LICENSE
+//  * Copyright (c) 2017-2018  Jeron A. Lau <jeron.lau@plopgrizzly.com>
+
+use Vec3;
+use Mat4;
+
+/// A 3x3 Matrix, stored column by column like `Mat4`.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Mat3(pub [f32; 9]);
+
+impl Mat3 {
+	/// Create a no-op transform (identity matrix).
+	pub fn identity() -> Mat3 {
+		Mat3([1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0])
+	}
+
+	/// Get the upper-left 3x3 of a `Mat4`.
+	pub fn from_mat4(m: Mat4) -> Mat3 {
+		Mat3([
+			m.0[0], m.0[1], m.0[2],
+			m.0[4], m.0[5], m.0[6],
+			m.0[8], m.0[9], m.0[10],
+		])
+	}
+
+	/// Embed into the upper-left of a `Mat4`, with no translation.
+	pub fn to_mat4(self) -> Mat4 {
+		Mat4([
+			self.0[0], self.0[1], self.0[2], 0.0,
+			self.0[3], self.0[4], self.0[5], 0.0,
+			self.0[6], self.0[7], self.0[8], 0.0,
+			0.0, 0.0, 0.0, 1.0,
+		])
+	}
+}
+
+impl From<Mat4> for Mat3 {
+	fn from(m: Mat4) -> Mat3 {
+		Mat3::from_mat4(m)
+	}
+}
+
+impl From<Mat3> for Mat4 {
+	fn from(m: Mat3) -> Mat4 {
+		m.to_mat4()
+	}
+}
+
+impl ::std::ops::Mul<Vec3> for Mat3 {
+	type Output = Vec3;
+
+	fn mul(self, rhs: Vec3) -> Self::Output {
+		let x = self.0[0]*rhs.x + self.0[3]*rhs.y + self.0[6]*rhs.z;
+		let y = self.0[1]*rhs.x + self.0[4]*rhs.y + self.0[7]*rhs.z;
+		let z = self.0[2]*rhs.x + self.0[5]*rhs.y + self.0[8]*rhs.z;
+
+		Vec3::new(x, y, z)
+	}
+}
+
+impl ::std::fmt::Display for Mat3 {
+	fn fmt(&self, fmtr: &mut ::std::fmt::Formatter) ->
+		::std::result::Result<(), ::std::fmt::Error>
+	{
+		write!(fmtr, "{:?}", self.0)
+	}
+}
+
+#[test]
+fn test_mat3_mat4_conversion() {
+	assert!(Mat3::from(::IDENTITY) == Mat3::identity());
+	assert!(Mat4::from(Mat3::identity()) == ::IDENTITY);
+
+	let rotation = ::IDENTITY.rotate(0.25, 0.5, 0.1);
+	let m: Mat3 = rotation.translate(1.0, 2.0, 3.0).into();
+	let v = Vec3::new(0.5, -1.0, 2.0);
+	assert!(m * v == v.transform_dir(rotation));
+	let back = Mat4::from(m);
+	for i in 0..16 {
+		assert!((back.0[i] - rotation.0[i]).abs() < 0.0001);
+	}
+}
@@ -5,12 +5,15 @@ use Vec4;
 use Vec3;
 use Plane;
 use Frustum;
+use Quaternion;
+use Mat3;
 
 /// A no-op transform (identity matrix).
 pub const IDENTITY: Mat4 = Mat4([1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0,
 	0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,]);
 
-/// A 4x4 Matrix
+/// A 4x4 Matrix, stored column by column: row `r` of column `c` is
+/// `self.0[c * 4 + r]`, so the translation is in `self.0[12..15]`.
 #[derive(Clone, Copy, PartialEq)]
 pub struct Mat4(pub [f32; 16]);
 
@@ -30,6 +33,17 @@ impl Mat4 {
 		])
 	}
 
+	/// Multiply `self` by a scale transformation matrix, the same on all
+	/// three axes.
+	pub fn scale_uniform(self, s: f32) -> Mat4 {
+		self.scale(s, s, s)
+	}
+
+	/// Create a scale transformation matrix, the same on all three axes.
+	pub fn from_scale_uniform(s: f32) -> Mat4 {
+		IDENTITY.scale_uniform(s)
+	}
+
 	/// Multiply `self` by a translation matrix.
 	pub fn translate(self, x: f32, y: f32, z: f32) -> Mat4 {
 		self.matrix([
@@ -74,6 +88,416 @@ impl Mat4 {
 			nx,ny,nz,qw
 		])
 	}
+
+	/// Multiply `self` by a scale transformation matrix around `center`
+	/// instead of the origin.
+	pub fn scale_around_point(self, center: Vec3, x: f32, y: f32, z: f32)
+		-> Mat4
+	{
+		self.translate(-center.x, -center.y, -center.z)
+			.scale(x, y, z)
+			.translate(center.x, center.y, center.z)
+	}
+
+	/// Multiply `self` by a rotation matrix around `center` instead of the
+	/// origin.  `x`, `y` and `z` are in PI Radians, like `rotate()`.
+	pub fn rotate_around_point(self, center: Vec3, x: f32, y: f32, z: f32)
+		-> Mat4
+	{
+		self.translate(-center.x, -center.y, -center.z)
+			.rotate(x, y, z)
+			.translate(center.x, center.y, center.z)
+	}
+
+	/// Create a matrix with `s` for all four entries of the diagonal.  Unlike
+	/// `scale()`, this also scales the W component.
+	pub fn scalar(s: f32) -> Mat4 {
+		Mat4([
+			s,   0.0, 0.0, 0.0,
+			0.0, s,   0.0, 0.0,
+			0.0, 0.0, s,   0.0,
+			0.0, 0.0, 0.0, s,
+		])
+	}
+
+	/// Create a right-handed view matrix for a camera at `eye` looking at
+	/// `center`.  The camera looks down -Z in view space (OpenGL style).
+	pub fn look_at_rh(eye: Vec3, center: Vec3, up: Vec3) -> Mat4 {
+		Self::look_at(eye, center, up, false)
+	}
+
+	/// Create a left-handed view matrix for a camera at `eye` looking at
+	/// `center`.  The camera looks down +Z in view space (DirectX style).
+	pub fn look_at_lh(eye: Vec3, center: Vec3, up: Vec3) -> Mat4 {
+		Self::look_at(eye, center, up, true)
+	}
+
+	fn look_at(eye: Vec3, center: Vec3, up: Vec3, lh: bool) -> Mat4 {
+		let forward = (center - eye).normalize();
+		// View space Z axis
+		let z = if lh { forward } else { -forward };
+		let x = up.cross(z).normalize();
+		let y = z.cross(x);
+
+		Mat4([
+			x.x, y.x, z.x, 0.0,
+			x.y, y.y, z.y, 0.0,
+			x.z, y.z, z.z, 0.0,
+			-x.dot(eye), -y.dot(eye), -z.dot(eye), 1.0,
+		])
+	}
+
+	/// Create a right-handed perspective projection matrix (OpenGL style).
+	/// `fov_y` is in radians.
+	///
+	/// The camera looks down -Z, and depth from `near` to `far` is mapped
+	/// to -1 to 1 in clip space.
+	pub fn perspective_rh(fov_y: f32, aspect: f32, near: f32, far: f32)
+		-> Mat4
+	{
+		let f = 1.0 / (fov_y / 2.0).tan();
+
+		Mat4([
+			f / aspect, 0.0, 0.0, 0.0,
+			0.0, f, 0.0, 0.0,
+			0.0, 0.0, (far + near) / (near - far), -1.0,
+			0.0, 0.0, (2.0 * far * near) / (near - far), 0.0,
+		])
+	}
+
+	/// Create a left-handed perspective projection matrix (DirectX style).
+	/// `fov_y` is in radians.
+	///
+	/// Unlike `perspective_rh()`, the camera looks down +Z, and depth from
+	/// `near` to `far` is mapped to 0 to 1 in clip space.
+	pub fn perspective_lh(fov_y: f32, aspect: f32, near: f32, far: f32)
+		-> Mat4
+	{
+		let f = 1.0 / (fov_y / 2.0).tan();
+
+		Mat4([
+			f / aspect, 0.0, 0.0, 0.0,
+			0.0, f, 0.0, 0.0,
+			0.0, 0.0, far / (far - near), 1.0,
+			0.0, 0.0, -(near * far) / (far - near), 0.0,
+		])
+	}
+
+	/// Create a left-handed perspective projection matrix with no far plane
+	/// and reversed depth.  `fov_y` is in radians.
+	///
+	/// Like `perspective_lh()` the camera looks down +Z, but depth from
+	/// `near` to infinity is mapped to 1 to 0 in clip space.  This is the
+	/// limit of `perspective_lh()` as `far` goes to infinity, with depth
+	/// flipped, which spreads depth precision more evenly when using a
+	/// floating point depth buffer.
+	pub fn infinite_perspective(fov_y: f32, aspect: f32, near: f32) -> Mat4 {
+		let f = 1.0 / (fov_y / 2.0).tan();
+
+		Mat4([
+			f / aspect, 0.0, 0.0, 0.0,
+			0.0, f, 0.0, 0.0,
+			0.0, 0.0, 0.0, 1.0,
+			0.0, 0.0, near, 0.0,
+		])
+	}
+
+	/// Create an OpenGL style perspective projection matrix (like
+	/// `glFrustum`) from the edges of the near plane.  Unlike
+	/// `perspective_rh()`, the frustum can be off-center.
+	///
+	/// The camera looks down -Z, and depth from `near` to `far` is mapped
+	/// to -1 to 1 in clip space.
+	pub fn frustum(left: f32, right: f32, bottom: f32, top: f32, near: f32,
+		far: f32) -> Mat4
+	{
+		Mat4([
+			2.0 * near / (right - left), 0.0, 0.0, 0.0,
+			0.0, 2.0 * near / (top - bottom), 0.0, 0.0,
+			(right + left) / (right - left),
+			(top + bottom) / (top - bottom),
+			(far + near) / (near - far), -1.0,
+			0.0, 0.0, (2.0 * far * near) / (near - far), 0.0,
+		])
+	}
+
+	/// Get the transpose of the matrix (rows become columns).
+	pub fn transpose(self) -> Mat4 {
+		let mut out = [0.0; 16];
+
+		for c in 0..4 {
+			for r in 0..4 {
+				out[r * 4 + c] = self.0[c * 4 + r];
+			}
+		}
+
+		Mat4(out)
+	}
+
+	/// Get the determinant of the 3x3 matrix left when `row` and `col` are
+	/// removed.
+	fn minor(&self, row: usize, col: usize) -> f32 {
+		let mut m = [0.0; 9];
+		let mut i = 0;
+
+		for c in (0..4).filter(|c| *c != col) {
+			for r in (0..4).filter(|r| *r != row) {
+				m[i] = self.0[c * 4 + r];
+				i += 1;
+			}
+		}
+
+		m[0] * (m[4] * m[8] - m[7] * m[5])
+			- m[3] * (m[1] * m[8] - m[7] * m[2])
+			+ m[6] * (m[1] * m[5] - m[4] * m[2])
+	}
+
+	/// Get the matrix of cofactors (signed minors).
+	pub fn cofactor_matrix(self) -> Mat4 {
+		let mut out = [0.0; 16];
+
+		for c in 0..4 {
+			for r in 0..4 {
+				let sign = if (r + c) % 2 == 0 { 1.0 } else { -1.0 };
+				out[c * 4 + r] = sign * self.minor(r, c);
+			}
+		}
+
+		Mat4(out)
+	}
+
+	/// Get the adjugate (transpose of the cofactor matrix).
+	pub fn adjugate(self) -> Mat4 {
+		self.cofactor_matrix().transpose()
+	}
+
+	/// Get the determinant of the matrix.
+	pub fn determinant(self) -> f32 {
+		let cofactors = self.cofactor_matrix();
+
+		(0..4).map(|c| self.0[c * 4] * cofactors.0[c * 4]).sum()
+	}
+
+	/// Check if the matrix has an inverse, treating a determinant within
+	/// `epsilon` of 0 as 0.
+	pub fn is_invertible(self, epsilon: f32) -> bool {
+		self.determinant().abs() > epsilon
+	}
+
+	/// Make the rotation part (upper-left 3x3) orthonormal again, using the
+	/// Gram-Schmidt process on its columns.  This fixes drift from many
+	/// small rotations being multiplied together.  The direction of the X
+	/// column and the translation are kept.
+	pub fn orthogonalize(self) -> Mat4 {
+		let column = |i: usize| {
+			let c = self.column(i);
+			Vec3::new(c.x, c.y, c.z)
+		};
+		let (x, y, z) = ::gram_schmidt(column(0), column(1), column(2));
+		let mut out = self;
+
+		out.set_column(0, Vec4::new(x.x, x.y, x.z, 0.0));
+		out.set_column(1, Vec4::new(y.x, y.y, y.z, 0.0));
+		out.set_column(2, Vec4::new(z.x, z.y, z.z, 0.0));
+		out
+	}
+
+	/// Get the inverse of the matrix, `None` if it's not invertible.
+	pub fn inverse(self) -> Option<Mat4> {
+		let determinant = self.determinant();
+
+		if determinant == 0.0 {
+			return None;
+		}
+
+		Some(self.adjugate() / determinant)
+	}
+
+	/// Multiply the matrix by itself `n` times (`pow(0)` is `IDENTITY`).
+	pub fn pow(self, mut n: u32) -> Mat4 {
+		let mut result = IDENTITY;
+		let mut base = self;
+
+		// Square and multiply
+		while n > 0 {
+			if n & 1 == 1 {
+				result = result * base;
+			}
+			base = base * base;
+			n >>= 1;
+		}
+
+		result
+	}
+
+	/// Create a matrix that scales, then rotates, then translates.
+	pub fn from_trs(translation: Vec3, rotation: Quaternion, scale: Vec3)
+		-> Mat4
+	{
+		let Quaternion { x, y, z, w } = rotation;
+
+		Mat4([
+			(1.0 - 2.0 * (y * y + z * z)) * scale.x,
+			2.0 * (x * y + z * w) * scale.x,
+			2.0 * (x * z - y * w) * scale.x,
+			0.0,
+
+			2.0 * (x * y - z * w) * scale.y,
+			(1.0 - 2.0 * (x * x + z * z)) * scale.y,
+			2.0 * (y * z + x * w) * scale.y,
+			0.0,
+
+			2.0 * (x * z + y * w) * scale.z,
+			2.0 * (y * z - x * w) * scale.z,
+			(1.0 - 2.0 * (x * x + y * y)) * scale.z,
+			0.0,
+
+			translation.x, translation.y, translation.z, 1.0,
+		])
+	}
+
+	/// Create a rigid transformation matrix (no scale) that rotates, then
+	/// translates.
+	pub fn from_rotation_translation(rotation: Quaternion, translation: Vec3)
+		-> Mat4
+	{
+		Mat4::from_trs(translation, rotation, Vec3::one())
+	}
+
+	/// Create a rigid transformation matrix that rotates `angle` radians
+	/// around `axis`, then translates.
+	pub fn from_axis_angle_translation(axis: Vec3, angle: f32,
+		translation: Vec3) -> Mat4
+	{
+		Mat4::from_rotation_translation(
+			Quaternion::from_axis_angle(axis, angle), translation)
+	}
+
+	/// Split an affine transformation into translation, rotation and
+	/// scale, the opposite of `from_trs()`.  A mirrored matrix gets a
+	/// negative X scale.  Shear is lost.
+	pub fn decompose(self) -> (Vec3, Quaternion, Vec3) {
+		let m = self.0;
+		let translation = self.extract_translation();
+		let mut scale = self.extract_scale();
+
+		if self.determinant() < 0.0 {
+			scale.x = -scale.x;
+		}
+
+		// Upper-left 3x3 with the scale removed.
+		let r = Mat3([
+			m[0] / scale.x, m[1] / scale.x, m[2] / scale.x,
+			m[4] / scale.y, m[5] / scale.y, m[6] / scale.y,
+			m[8] / scale.z, m[9] / scale.z, m[10] / scale.z,
+		]);
+
+		(translation, Quaternion::from_mat3(r), scale)
+	}
+
+	/// Get the translation of an affine transformation.
+	pub fn extract_translation(self) -> Vec3 {
+		Vec3::new(self.0[12], self.0[13], self.0[14])
+	}
+
+	/// Get the scale of an affine transformation (always positive, unlike
+	/// `decompose()`).
+	pub fn extract_scale(self) -> Vec3 {
+		let m = self.0;
+
+		Vec3::new(
+			Vec3::new(m[0], m[1], m[2]).mag(),
+			Vec3::new(m[4], m[5], m[6]).mag(),
+			Vec3::new(m[8], m[9], m[10]).mag(),
+		)
+	}
+
+	/// Interpolate between `self` (`t` = 0) and `other` (`t` = 1) element
+	/// by element.
+	///
+	/// This is fast, but the result isn't a rotation unless the rotations
+	/// of both matrices are close, and it shrinks in between.  Use
+	/// `interpolate_trs()` to interpolate the rotation correctly.
+	pub fn interpolate(self, other: Mat4, t: f32) -> Mat4 {
+		self + (other - self) * t
+	}
+
+	/// Interpolate between `self` (`t` = 0) and `other` (`t` = 1) by
+	/// decomposing both into translation, rotation and scale, interpolating
+	/// each one, and putting them back together.
+	///
+	/// Unlike `interpolate()` this keeps rotations rigid (the rotation is
+	/// interpolated with `Quaternion::nlerp()`), but it's slower, and shear
+	/// is lost.
+	pub fn interpolate_trs(self, other: Mat4, t: f32) -> Mat4 {
+		let (t1, r1, s1) = self.decompose();
+		let (t2, r2, s2) = other.decompose();
+
+		Mat4::from_trs(t1.lerp(t2, t), r1.nlerp(r2, t), s1.lerp(s2, t))
+	}
+
+	/// Transform `p` as a position: `M * (p, 1)`, so translation applies.
+	/// This is the same as `self * p`.
+	pub fn mul_point(self, p: Vec3) -> Vec3 {
+		let x = self.0[0]*p.x + self.0[4]*p.y + self.0[8]*p.z + self.0[12];
+		let y = self.0[1]*p.x + self.0[5]*p.y + self.0[9]*p.z + self.0[13];
+		let z = self.0[2]*p.x + self.0[6]*p.y + self.0[10]*p.z + self.0[14];
+
+		Vec3::new(x, y, z)
+	}
+
+	/// Transform `d` as a direction: `M * (d, 0)`, so translation doesn't
+	/// apply.  This is the same as `d.transform_dir(self)`.
+	pub fn mul_dir(self, d: Vec3) -> Vec3 {
+		d.transform_dir(self)
+	}
+
+	/// Create a matrix from its 4 columns.
+	pub fn from_columns(c0: Vec4, c1: Vec4, c2: Vec4, c3: Vec4) -> Mat4 {
+		Mat4([
+			c0.x, c0.y, c0.z, c0.w,
+			c1.x, c1.y, c1.z, c1.w,
+			c2.x, c2.y, c2.z, c2.w,
+			c3.x, c3.y, c3.z, c3.w,
+		])
+	}
+
+	/// Create a matrix from its 4 rows.
+	pub fn from_rows(r0: Vec4, r1: Vec4, r2: Vec4, r3: Vec4) -> Mat4 {
+		Mat4::from_columns(r0, r1, r2, r3).transpose()
+	}
+
+	/// Get column `i` of the matrix.  Panics if `i` is not 0-3.
+	pub fn column(self, i: usize) -> Vec4 {
+		assert!(i < 4, "column must be 0-3, not {}", i);
+		Vec4::new(self.0[i * 4], self.0[i * 4 + 1], self.0[i * 4 + 2],
+			self.0[i * 4 + 3])
+	}
+
+	/// Get row `i` of the matrix.  Panics if `i` is not 0-3.
+	pub fn row(self, i: usize) -> Vec4 {
+		assert!(i < 4, "row must be 0-3, not {}", i);
+		Vec4::new(self.0[i], self.0[i + 4], self.0[i + 8],
+			self.0[i + 12])
+	}
+
+	/// Set column `i` of the matrix.  Panics if `i` is not 0-3.
+	pub fn set_column(&mut self, i: usize, v: Vec4) {
+		assert!(i < 4, "column must be 0-3, not {}", i);
+		self.0[i * 4] = v.x;
+		self.0[i * 4 + 1] = v.y;
+		self.0[i * 4 + 2] = v.z;
+		self.0[i * 4 + 3] = v.w;
+	}
+
+	/// Set row `i` of the matrix.  Panics if `i` is not 0-3.
+	pub fn set_row(&mut self, i: usize, v: Vec4) {
+		assert!(i < 4, "row must be 0-3, not {}", i);
+		self.0[i] = v.x;
+		self.0[i + 4] = v.y;
+		self.0[i + 8] = v.z;
+		self.0[i + 12] = v.w;
+	}
 }
 
 impl ::std::ops::Mul<Frustum> for Mat4 {
@@ -83,6 +507,7 @@ impl ::std::ops::Mul<Frustum> for Mat4 {
 		Frustum {
 			center: self * rhs.center,
 			radius: rhs.radius,
+			near: rhs.near,
 			wfov: rhs.wfov,
 			hfov: rhs.hfov,
 			xrot: rhs.xrot, // TODO
@@ -100,61 +525,18 @@ impl ::std::ops::Mul<Frustum> for Mat4 {
 impl ::std::ops::Mul<Plane> for Mat4 {
 	type Output = Plane;
 
+	/// Same as `rhs.transform(self)`.
 	fn mul(self, rhs: Plane) -> Self::Output {
-		let facing = rhs.facing.transform_dir(self);
-		// translation vector
-		let t = Vec3::new(self.0[12], self.0[13], self.0[14]);
-		//
-		if t == Vec3::zero() {
-			return Plane { facing, offset: rhs.offset };
-		}
-		// Angle between normal and translation
-		let mut a = facing.angle(t).abs();
-
-		// If more than full circle, reduce
-		while a > ::std::f32::consts::PI * 2.0 {
-			a -= ::std::f32::consts::PI * 2.0;
-		}
-
-		let mut b = 1.0;
-
-		// If value is over 90° it can be reduced
-		if a > ::std::f32::consts::PI / 2.0 {
-			// 90°-180° quadrant
-			if a < ::std::f32::consts::PI {
-				a = ::std::f32::consts::PI - a;
-				b = -b;
-			// 180°-270° quadrant
-			} else if a < 3.0 * ::std::f32::consts::PI / 2.0 {
-				a -= ::std::f32::consts::PI;
-				b = -b;
-			// 270°-360° quadrant
-			} else {
-				a = (2.0 * ::std::f32::consts::PI) - a;
-			}
-		}
-
-		// if a == 90°
-		let offset = rhs.offset + if a >= ::std::f32::consts::PI / 2.0 {
-			0.0
-		} else {
-			a.cos() * t.mag() * b
-		};
-
-		Plane { facing, offset }
+		rhs.transform(self)
 	}
 }
 
 impl ::std::ops::Mul<Vec3> for Mat4 {
 	type Output = Vec3;
 
-	/// Transform as a position.
+	/// Transform as a position, same as `mul_point()`.
 	fn mul(self, rhs: Vec3) -> Self::Output {
-		let x = self.0[0]*rhs.x + self.0[4]*rhs.y + self.0[8]*rhs.z + self.0[12]*1.0;
-		let y = self.0[1]*rhs.x + self.0[5]*rhs.y + self.0[9]*rhs.z + self.0[13]*1.0;
-		let z = self.0[2]*rhs.x + self.0[6]*rhs.y + self.0[10]*rhs.z + self.0[14]*1.0;
-
-		Vec3::new(x, y, z)
+		self.mul_point(rhs)
 	}
 }
 
@@ -216,6 +598,70 @@ impl ::std::ops::Mul<Mat4> for Mat4 {
 	}
 }
 
+impl ::std::ops::Add<Mat4> for Mat4 {
+	type Output = Mat4;
+
+	fn add(mut self, rhs: Mat4) -> Self::Output {
+		for i in 0..16 {
+			self.0[i] += rhs.0[i];
+		}
+		self
+	}
+}
+
+impl ::std::ops::Sub<Mat4> for Mat4 {
+	type Output = Mat4;
+
+	fn sub(mut self, rhs: Mat4) -> Self::Output {
+		for i in 0..16 {
+			self.0[i] -= rhs.0[i];
+		}
+		self
+	}
+}
+
+impl ::std::ops::Mul<f32> for Mat4 {
+	type Output = Mat4;
+
+	fn mul(mut self, rhs: f32) -> Self::Output {
+		self *= rhs;
+		self
+	}
+}
+
+impl ::std::ops::Mul<Mat4> for f32 {
+	type Output = Mat4;
+
+	fn mul(self, rhs: Mat4) -> Self::Output {
+		rhs * self
+	}
+}
+
+impl ::std::ops::MulAssign<f32> for Mat4 {
+	fn mul_assign(&mut self, rhs: f32) {
+		for i in self.0.iter_mut() {
+			*i *= rhs;
+		}
+	}
+}
+
+impl ::std::ops::Div<f32> for Mat4 {
+	type Output = Mat4;
+
+	fn div(mut self, rhs: f32) -> Self::Output {
+		self /= rhs;
+		self
+	}
+}
+
+impl ::std::ops::DivAssign<f32> for Mat4 {
+	fn div_assign(&mut self, rhs: f32) {
+		for i in self.0.iter_mut() {
+			*i /= rhs;
+		}
+	}
+}
+
 impl ::std::fmt::Display for Mat4 {
 	fn fmt(&self, fmtr: &mut ::std::fmt::Formatter) ->
 		::std::result::Result<(), ::std::fmt::Error>
@@ -223,3 +669,324 @@ impl ::std::fmt::Display for Mat4 {
 		write!(fmtr, "{:?}", self.0)
 	}
 }
+
+#[test]
+fn test_mat4_rows_columns() {
+	let a = IDENTITY.rotate(0.25, 0.5, 0.1).translate(1.0, 2.0, 3.0);
+	let mut b = Mat4([0.0; 16]);
+	let mut c = Mat4([0.0; 16]);
+	for i in 0..4 {
+		b.set_column(i, a.column(i));
+		c.set_row(i, a.row(i));
+	}
+	assert!(a == b);
+	assert!(a == c);
+	assert!(a.column(3) == Vec4::new(a.0[12], a.0[13], a.0[14], a.0[15]));
+	assert!(a.row(0) == Vec4::new(a.0[0], a.0[4], a.0[8], a.0[12]));
+}
+
+#[test]
+fn test_mat4_infinite_perspective() {
+	let pi = ::std::f32::consts::PI;
+	let m = Mat4::infinite_perspective(pi / 2.0, 2.0, 0.5);
+	let depth = |z: f32| {
+		let clip = m * Vec4::new(1.0, 1.0, z, 1.0);
+		clip.z / clip.w
+	};
+
+	assert!(depth(0.5) == 1.0);
+	assert!(depth(1.0) == 0.5);
+	assert!(depth(1.0e6) > 0.0 && depth(1.0e6) < 0.000001);
+	// X and Y are the same as with a far plane.
+	let lh = Mat4::perspective_lh(pi / 2.0, 2.0, 0.5, 100.0);
+	let (a, b) = (m * Vec4::new(1.0, -2.0, 3.0, 1.0),
+		lh * Vec4::new(1.0, -2.0, 3.0, 1.0));
+	assert!(a.x == b.x && a.y == b.y && a.w == b.w);
+}
+
+#[test]
+fn test_mat4_mul_point_dir() {
+	let m = IDENTITY.rotate(0.25, 0.5, 0.1).translate(1.0, 2.0, 3.0);
+	let v = Vec3::new(0.5, -1.0, 2.0);
+	assert!(m.mul_point(v) == m * v);
+	assert!(m.mul_dir(v) == v.transform_dir(m));
+	assert!(m.mul_point(v) - m.mul_dir(v) == Vec3::new(1.0, 2.0, 3.0));
+}
+
+#[test]
+fn test_mat4_from_columns_rows() {
+	let m = IDENTITY.rotate(0.25, 0.5, 0.1).translate(1.0, 2.0, 3.0);
+	assert!(Mat4::from_columns(m.column(0), m.column(1), m.column(2),
+		m.column(3)) == m);
+	assert!(Mat4::from_rows(m.row(0), m.row(1), m.row(2), m.row(3)) == m);
+	assert!(Mat4::from_rows(m.row(0), m.row(1), m.row(2), m.row(3))
+		.column(3) == Vec4::new(1.0, 2.0, 3.0, 1.0));
+}
+
+#[test]
+fn test_mat4_look_at() {
+	let eye = Vec3::new(1.0, 2.0, 5.0);
+	let center = Vec3::new(1.0, 2.0, 0.0);
+	let up = Vec3::new(0.0, 1.0, 0.0);
+	let rh = Mat4::look_at_rh(eye, center, up);
+	let lh = Mat4::look_at_lh(eye, center, up);
+
+	assert!(rh * eye == Vec3::zero());
+	assert!(lh * eye == Vec3::zero());
+	assert!(rh * center == Vec3::new(0.0, 0.0, -5.0));
+	assert!(lh * center == Vec3::new(0.0, 0.0, 5.0));
+	assert!(rh * Vec3::new(1.0, 3.0, 0.0) == Vec3::new(0.0, 1.0, -5.0));
+	assert!(lh * Vec3::new(1.0, 3.0, 0.0) == Vec3::new(0.0, 1.0, 5.0));
+	assert!(rh * Vec3::new(2.0, 2.0, 0.0) == Vec3::new(1.0, 0.0, -5.0));
+	assert!(lh * Vec3::new(2.0, 2.0, 0.0) == Vec3::new(-1.0, 0.0, 5.0));
+
+	// Both are rotations (no mirroring), with `center` straight ahead.
+	let eye = Vec3::new(-3.0, 1.0, 2.0);
+	let center = Vec3::new(4.0, -2.0, 0.5);
+	let up = Vec3::new(0.2, 1.0, 0.1);
+	let dist = eye.dist(center);
+	let rh = Mat4::look_at_rh(eye, center, up);
+	let lh = Mat4::look_at_lh(eye, center, up);
+	assert!((rh.determinant() - 1.0).abs() < 0.0001);
+	assert!((lh.determinant() - 1.0).abs() < 0.0001);
+	assert!((rh * center).dist(Vec3::new(0.0, 0.0, -dist)) < 0.0001);
+	assert!((lh * center).dist(Vec3::new(0.0, 0.0, dist)) < 0.0001);
+}
+
+#[test]
+fn test_mat4_perspective() {
+	let depth = |m: Mat4, z: f32| {
+		let clip = m * Vec4::new(0.0, 0.0, z, 1.0);
+		clip.z / clip.w
+	};
+	let lh = Mat4::perspective_lh(1.0, 1.5, 0.5, 100.0);
+	let rh = Mat4::perspective_rh(1.0, 1.5, 0.5, 100.0);
+
+	assert!(depth(lh, 0.5).abs() < 0.0001);
+	assert!((depth(lh, 100.0) - 1.0).abs() < 0.0001);
+	assert!((depth(rh, -0.5) + 1.0).abs() < 0.0001);
+	assert!((depth(rh, -100.0) - 1.0).abs() < 0.0001);
+}
+
+#[test]
+fn test_mat4_frustum() {
+	let ndc = |m: Mat4, p: Vec3| {
+		let clip = m * Vec4::new(p.x, p.y, p.z, 1.0);
+		Vec3::new(clip.x, clip.y, clip.z) / clip.w
+	};
+
+	// Symmetric is the same as perspective_rh()
+	let top = 0.5 * (0.5f32).tan();
+	let a = Mat4::frustum(-top * 1.5, top * 1.5, -top, top, 0.5, 100.0);
+	let b = Mat4::perspective_rh(1.0, 1.5, 0.5, 100.0);
+	for i in 0..16 {
+		assert!((a.0[i] - b.0[i]).abs() < 0.0001);
+	}
+
+	// Off-center
+	let m = Mat4::frustum(-1.0, 3.0, 0.0, 2.0, 1.0, 10.0);
+	assert!(ndc(m, Vec3::new(-1.0, 0.0, -1.0))
+		.dist(Vec3::new(-1.0, -1.0, -1.0)) < 0.0001);
+	assert!(ndc(m, Vec3::new(30.0, 20.0, -10.0))
+		.dist(Vec3::new(1.0, 1.0, 1.0)) < 0.0001);
+}
+
+#[test]
+fn test_mat4_adjugate() {
+	let a = IDENTITY.rotate(0.25, 0.5, 0.1).translate(1.0, 2.0, 3.0)
+		.scale(2.0, 1.0, 0.5);
+	let d = a.determinant();
+	assert!((d - 1.0).abs() < 0.0001);
+	let b = a * a.adjugate();
+	for i in 0..16 {
+		assert!((b.0[i] - Mat4::scalar(d).0[i]).abs() < 0.0001);
+	}
+
+	let c = a * a.inverse().unwrap();
+	for i in 0..16 {
+		assert!((c.0[i] - IDENTITY.0[i]).abs() < 0.0001);
+	}
+	assert!(IDENTITY.scale(1.0, 0.0, 1.0).inverse().is_none());
+	assert!(a.transpose().transpose() == a);
+}
+
+#[test]
+fn test_mat4_is_invertible() {
+	let mut singular = IDENTITY.translate(1.0, 2.0, 3.0);
+	singular.set_row(1, Vec4::new(0.0, 0.0, 0.0, 0.0));
+	assert!(!singular.is_invertible(0.0001));
+	assert!(singular.inverse().is_none());
+
+	assert!(IDENTITY.rotate(0.25, 0.5, 0.1).is_invertible(0.0001));
+	assert!(!Mat4::from_scale_uniform(0.01).is_invertible(0.0001));
+	assert!(Mat4::from_scale_uniform(0.01).is_invertible(0.0));
+}
+
+#[test]
+fn test_mat4_orthogonalize() {
+	let close = |a: Mat4, b: Mat4| (0..16).all(|i| (a.0[i] - b.0[i]).abs()
+		< 0.0001);
+	// Nearly a rotation
+	let mut m = IDENTITY.rotate(0.25, 0.5, 0.1);
+	m.0[0] += 0.01;
+	m.0[5] -= 0.02;
+	m.0[9] += 0.015;
+	assert!(!close(m.transpose() * m, IDENTITY));
+	let o = m.orthogonalize();
+	assert!(close(o.transpose() * o, IDENTITY));
+	assert!(close(o.orthogonalize(), o));
+
+	let t = m.translate(1.0, 2.0, 3.0).orthogonalize();
+	assert!(t.extract_translation() == Vec3::new(1.0, 2.0, 3.0));
+}
+
+#[test]
+fn test_mat4_scalar() {
+	let v = Vec3::new(1.0, -2.0, 3.5);
+	assert!(Mat4::scalar(2.0) * v == v * 2.0);
+	assert!(Mat4::scalar(1.0) == IDENTITY);
+	assert!(Mat4::from_scale_uniform(2.0) == IDENTITY.scale(2.0, 2.0, 2.0));
+	assert!(Mat4::from_scale_uniform(2.0) * v == Mat4::scalar(2.0) * v);
+	assert!(Mat4::scalar(3.0) * Vec4::new(1.0, 1.0, 1.0, 1.0)
+		== Vec4::new(3.0, 3.0, 3.0, 3.0));
+}
+
+#[test]
+fn test_mat4_pow() {
+	let a = IDENTITY.rotate(0.25, 0.5, 0.1).translate(1.0, 2.0, 3.0);
+	assert!(a.pow(0) == IDENTITY);
+	assert!(a.pow(1) == a);
+	assert!(a.pow(2) == a * a);
+	let b = a.pow(5);
+	let c = a * a * a * a * a;
+	for i in 0..16 {
+		assert!((b.0[i] - c.0[i]).abs() < 0.0001);
+	}
+}
+
+#[test]
+fn test_mat4_scalar_ops() {
+	let a = IDENTITY.rotate(0.25, 0.5, 0.1).translate(1.0, 2.0, 3.0);
+	assert!(a * 2.0 + a * -2.0 == Mat4([0.0; 16]));
+	assert!(2.0 * a == a + a);
+	assert!((a * 4.0) / 4.0 == a);
+	let mut b = a;
+	b *= 0.5;
+	b /= 0.5;
+	assert!(b == a);
+	assert!(a - a == Mat4([0.0; 16]));
+
+	// Dividing by the determinant scales every element, which turns the
+	// adjugate into the inverse.
+	let m = a.scale(2.0, 3.0, 0.5);
+	let det = m.determinant();
+	let scaled = m / det;
+	for i in 0..16 {
+		assert!(scaled.0[i] == m.0[i] / det);
+	}
+	let inverse = m.inverse().unwrap();
+	let adj = m.adjugate() / det;
+	for i in 0..16 {
+		assert!((adj.0[i] - inverse.0[i]).abs() < 0.0001);
+	}
+	// Dividing by the 4th root normalizes the determinant to 1.
+	assert!(((m / det.powf(0.25)).determinant() - 1.0).abs() < 0.0001);
+}
+
+#[test]
+fn test_mat4_trs() {
+	let close = |a: Vec3, b: Vec3| a.dist(b) < 0.0001;
+	let t = Vec3::new(1.0, -2.0, 3.0);
+	let r = Quaternion::from_axis_angle(Vec3::new(1.0, 2.0, -1.0), 2.5);
+	let s = Vec3::new(2.0, 0.5, 3.0);
+	let m = Mat4::from_trs(t, r, s);
+
+	let v = Vec3::new(0.5, -1.5, 2.0);
+	let sv = Vec3::new(v.x * s.x, v.y * s.y, v.z * s.z);
+	assert!(close(m * v, r * sv + t));
+
+	let (t2, r2, s2) = m.decompose();
+	assert!(close(t2, t) && close(s2, s));
+	assert!(r2.angle_between(r) < 0.001);
+}
+
+#[test]
+fn test_mat4_scale_around_point() {
+	let center = Vec3::new(1.0, -2.0, 3.0);
+	let m = IDENTITY.scale_around_point(center, 2.0, 3.0, 0.5);
+	assert!(m * center == center);
+	assert!(m * (center + Vec3::new(1.0, 1.0, 1.0))
+		== center + Vec3::new(2.0, 3.0, 0.5));
+	assert!(IDENTITY.scale_around_point(center, 1.0, 1.0, 1.0) == IDENTITY);
+}
+
+#[test]
+fn test_mat4_rotate_around_point() {
+	let center = Vec3::new(1.0, -2.0, 3.0);
+	let m = IDENTITY.rotate_around_point(center, 0.25, -0.1, 0.5);
+	assert!((m * center).dist(center) < 0.0001);
+
+	// Same as rotating around the origin, relative to the center.
+	let r = IDENTITY.rotate(0.25, -0.1, 0.5);
+	let p = Vec3::new(4.0, 1.0, -2.0);
+	assert!((m * p).dist(r * (p - center) + center) < 0.0001);
+}
+
+#[test]
+fn test_mat4_extract() {
+	let m = IDENTITY.translate(1.0, 2.0, 3.0);
+	assert!(m.extract_translation() == Vec3::new(1.0, 2.0, 3.0));
+	assert!(m.extract_scale() == Vec3::one());
+	let m = IDENTITY.scale(2.0, 3.0, -4.0).translate(1.0, 2.0, 3.0);
+	assert!(m.extract_translation() == Vec3::new(1.0, 2.0, 3.0));
+	assert!(m.extract_scale() == Vec3::new(2.0, 3.0, 4.0));
+}
+
+#[test]
+fn test_mat4_from_rotation_translation() {
+	let r = Quaternion::from_axis_angle(Vec3::new(-1.0, 0.5, 2.0), 1.2);
+	let t = Vec3::new(4.0, 0.0, -1.0);
+	let m = Mat4::from_rotation_translation(r, t);
+
+	for v in [Vec3::zero(), Vec3::new(1.0, 2.0, 3.0),
+		Vec3::new(-7.0, 0.5, 0.25)].iter()
+	{
+		assert!((m * *v).dist(r.mul_vec3(*v) + t) < 0.0001);
+	}
+	assert!(m.row(3) == Vec4::new(0.0, 0.0, 0.0, 1.0));
+}
+
+#[test]
+fn test_mat4_from_axis_angle_translation() {
+	let axis = Vec3::new(0.5, 1.0, -2.0);
+	let t = Vec3::new(-1.0, 3.0, 2.0);
+	let m = Mat4::from_axis_angle_translation(axis, 0.8, t);
+	let chained = Mat4::from_rotation_translation(
+		Quaternion::from_axis_angle(axis, 0.8), Vec3::zero())
+		.translate(t.x, t.y, t.z);
+
+	for i in 0..16 {
+		assert!((m.0[i] - chained.0[i]).abs() < 0.0001);
+	}
+	let v = Vec3::new(2.0, -1.0, 0.5);
+	assert!((m * v).dist(v.rotate_around_axis(axis, 0.8) + t) < 0.0001);
+}
+
+#[test]
+fn test_mat4_interpolate() {
+	let a = Mat4::from_trs(Vec3::zero(), Quaternion::identity(),
+		Vec3::one());
+	let b = Mat4::from_trs(Vec3::new(2.0, 0.0, 0.0),
+		Quaternion::from_rotation_z(::std::f32::consts::PI),
+		Vec3::new(3.0, 3.0, 3.0));
+	let x = Vec3::new(1.0, 0.0, 0.0);
+
+	assert!(a.interpolate(b, 0.0) == a && a.interpolate(b, 1.0) == b);
+	// Element lerp of a half turn collapses X at the halfway point...
+	assert!((a.interpolate(b, 0.5) * x).dist(Vec3::new(0.0, 0.0, 0.0))
+		< 0.0001);
+	// ...but TRS interpolation turns it a quarter turn and scales it by 2.
+	let mid = a.interpolate_trs(b, 0.5) * x;
+	assert!((mid.dist(Vec3::new(1.0, 2.0, 0.0)) < 0.0001)
+		|| (mid.dist(Vec3::new(1.0, -2.0, 0.0)) < 0.0001));
+}
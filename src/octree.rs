@@ -9,7 +9,7 @@ use Vec3;
 use BCube;
 use BBox;
 use Collider;
-// use Frustum;
+use Frustum;
 
 /// An octree is a DAG that can quickly search for points in 3D space.
 ///
@@ -235,6 +235,19 @@ impl Node {
 		Some(())
 	}
 
+	/// Add a collider to a leaf node.
+	fn leaf_add_collider(&mut self, id: Id) -> Option<()> {
+		assert!(self.is_leaf());
+		// Skip 0 (LEAF sentinel) and 15 (link)
+		for i in 1..=14 {
+			if self.child[i].is_none() {
+				self.child[i] = id;
+				return Some(());
+			}
+		}
+		None
+	}
+
 	/// Remove a collider from a branch node.
 	fn branch_remove_collider(&mut self, id: Id) -> Option<()> {
 		assert!(self.is_branch());
@@ -513,9 +526,27 @@ impl<T> Octree<T> where T: Collider {
 			if self.nodes[node_id].branch_add_collider(id)
 				.is_none() // Is full, still!
 			{
+				self.link_add_collider(id, node_id);
+			}
+		}
+	}
+
+	/// Add a collider to the leaves linked from a full node, making a new
+	/// link leaf if they are all full too.
+	fn link_add_collider(&mut self, id: Id, mut node_id: usize) {
+		loop {
+			if let Some(link) = self.nodes[node_id].link() {
+				if self.nodes[link].leaf_add_collider(id).is_some() {
+					return;
+				}
+				node_id = link;
+			} else {
 				let link_id = self.new_leaf();
-				self.nodes[node_id].child[LINK]
-					= link_id;
+				self.nodes[node_id].child[LINK] = link_id;
+				self.nodes[{ let l: usize = link_id.into(); l }]
+					.leaf_add_collider(id)
+					.unwrap(); // new leaf, shouldn't fail.
+				return;
 			}
 		}
 	}
@@ -713,6 +744,95 @@ impl<T> Octree<T> where T: Collider {
 
 		None // Don't remove this node
 	}
+
+	/// Get all colliders whose `BBox` collides with `range`.
+	pub fn query_range<'a>(&'a self, range: BCube)
+		-> impl Iterator<Item = &'a T>
+	{
+		let range = range.to_bbox();
+
+		self.query(&|bbox| bbox.collide(range)).into_iter()
+	}
+
+	/// Get all colliders whose `BBox` contains point `p`.
+	pub fn query_point<'a>(&'a self, p: Vec3)
+		-> impl Iterator<Item = &'a T>
+	{
+		self.query(&|bbox| bbox.collide_vec3(p)).into_iter()
+	}
+
+	/// Get all colliders whose `BBox` intersects `frustum`.  Like
+	/// `BBox::intersects_frustum()`, this may give false positives near the
+	/// edges of the frustum.
+	pub fn query_frustum<'a>(&'a self, frustum: Frustum)
+		-> impl Iterator<Item = &'a T>
+	{
+		self.query(&|bbox| bbox.intersects_frustum(frustum)).into_iter()
+	}
+
+	/// Get all colliders for which `test` passes on their `BBox`, skipping
+	/// branches for which `test` fails on the region they cover.
+	fn query<'a, F>(&'a self, test: &F) -> Vec<&'a T>
+		where F: Fn(BBox) -> bool
+	{
+		let mut found = vec![];
+
+		if self.root.is_some() {
+			// Colliders at the root aren't always inside the root
+			// bcube, so the root region is unbounded.
+			let inf = ::std::f32::INFINITY;
+			let region = BBox::new_unchecked(Vec3::new(-inf, -inf, -inf),
+				Vec3::new(inf, inf, inf));
+			let root = self.root;
+			self.query_inside(test, root, self.bcube, region,
+				&mut found);
+		}
+
+		found
+	}
+
+	/// Recursive part of `query()`.  `region` is the space that colliders
+	/// in this branch may take up.
+	fn query_inside<'a, F>(&'a self, test: &F, node_id: Id, bcube: BCube,
+		region: BBox, found: &mut Vec<&'a T>) where F: Fn(BBox) -> bool
+	{
+		if !test(region) {
+			return;
+		}
+
+		let node_id: usize = node_id.into();
+
+		// Colliders in this branch, and in any linked leaves.
+		let mut link = Some(node_id);
+		let mut first = 8;
+		while let Some(i) = link {
+			for id in &self.nodes[i].child[first..=14] {
+				if id.is_some() && test(self[*id].bbox()) {
+					found.push(&self[*id]);
+				}
+			}
+			link = self.nodes[i].link();
+			first = 1;
+		}
+
+		// Descend into child branches.
+		let e = ::std::f32::EPSILON;
+		let c = bcube.center;
+		for ch in 0..8 {
+			let j = self.nodes[node_id].child[ch];
+			if j.is_none() {
+				continue;
+			}
+
+			let mut r = region;
+			if ch & 4 == 0 { r.max.x = c.x + e } else { r.min.x = c.x - e }
+			if ch & 2 == 0 { r.max.y = c.y + e } else { r.min.y = c.y - e }
+			if ch & 1 == 0 { r.max.z = c.z + e } else { r.min.z = c.z - e }
+
+			self.query_inside(test, j, Node::child_bcube(ch, bcube), r,
+				found);
+		}
+	}
 }
 
 impl<T> ::std::ops::Index<Id> for Octree<T> where T: Collider {
@@ -778,3 +898,82 @@ impl<T> fmt::Debug for Octree<T> where T: Collider {
 		write!(f, "")
 	}
 }
+
+#[cfg(test)]
+struct TestCollider(BBox);
+
+#[cfg(test)]
+impl Collider for TestCollider {
+	fn bbox(&self) -> BBox {
+		self.0
+	}
+}
+
+#[test]
+fn test_octree_query() {
+	let mut rng = ::test_util::Lcg::new(0x2545F491);
+	let mut rand = || rng.next_f32();
+
+	let mut octree = Octree::new();
+	let mut boxes = vec![];
+	for _ in 0..1000 {
+		let p = Vec3::new(rand(), rand(), rand());
+		let bbox = BBox::new(p, p + Vec3::new(0.5, 0.25, 0.5));
+		octree.add(TestCollider(bbox));
+		boxes.push(bbox);
+	}
+
+	// Every collider is found when querying the whole space.
+	let all = BCube { center: Vec3::new(0.0, 0.0, 0.0), half_len: 100.0 };
+	assert!(octree.query_range(all).count() == 1000);
+
+	for _ in 0..20 {
+		let range = BCube {
+			center: Vec3::new(rand(), rand(), rand()),
+			half_len: 10.0,
+		};
+		let expected = boxes.iter()
+			.filter(|b| b.collide_bcube(range))
+			.count();
+		let found: Vec<&TestCollider> = octree.query_range(range)
+			.collect();
+		assert!(found.len() == expected);
+		assert!(found.iter().all(|c| c.0.collide_bcube(range)));
+	}
+
+	for b in boxes.iter().take(20) {
+		let p = b.center();
+		let expected = boxes.iter().filter(|b| b.collide_vec3(p)).count();
+		assert!(octree.query_point(p).count() == expected);
+	}
+}
+
+#[test]
+fn test_octree_query_frustum() {
+	let mut octree = Octree::new();
+	let cube = |x, y, z| {
+		let p = Vec3::new(x, y, z);
+		TestCollider(BBox::new(p - 0.5, p + 0.5))
+	};
+
+	// In front of the camera
+	octree.add(cube(0.0, 0.0, 20.0));
+	octree.add(cube(5.0, -3.0, 50.0));
+	octree.add(cube(-10.0, 10.0, 15.0));
+	// Behind the camera
+	octree.add(cube(0.0, 0.0, -20.0));
+	octree.add(cube(5.0, 5.0, -50.0));
+	// Off to the side, and too far away
+	octree.add(cube(80.0, 0.0, 10.0));
+	octree.add(cube(0.0, 0.0, 200.0));
+
+	let half_pi = ::std::f32::consts::PI / 2.0;
+	let frustum = Frustum::new(Vec3::new(0.0, 0.0, 0.0), 100.0, 0.0, 0.0,
+		half_pi, half_pi);
+	let found: Vec<&TestCollider> = octree.query_frustum(frustum)
+		.collect();
+
+	assert!(found.len() == 3);
+	assert!(found.iter().all(|c| c.0.center().z > 0.0
+		&& c.0.center().z < 100.0 && c.0.center().x < 80.0));
+}
@@ -5,6 +5,9 @@ use std::fmt;
 
 use Vec3;
 use BCube;
+use BBox;
+use Mat4;
+use Segment;
 
 #[derive(Clone, Copy, PartialEq)]
 /// A `Plane`
@@ -27,6 +30,92 @@ impl Plane {
 		Plane { facing: dir.normalize(), offset: ofs }
 	}
 
+	/// Create a new plane facing `normal`, that passes through `point`.
+	pub fn from_normal_point(normal: Vec3, point: Vec3) -> Plane {
+		let facing = normal.normalize();
+
+		Plane { facing, offset: facing.dot(point) }
+	}
+
+	/// Get how far the plane is from the origin in the direction of
+	/// `facing`.  This is the `offset`, so positive means that the origin
+	/// is behind the plane.
+	pub fn signed_distance_to_origin(&self) -> f32 {
+		self.offset
+	}
+
+	/// Get the same plane facing the other way.
+	pub fn flip(&self) -> Plane {
+		Plane { facing: -self.facing, offset: -self.offset }
+	}
+
+	/// Transform the plane by a matrix.  If `m` isn't invertible (like a
+	/// scale of 0 on an axis), this is the plane that everything gets
+	/// flattened onto, or has a NaN `facing` if it's flattened further to a
+	/// line or point.  Use `try_transform()` to check for this instead.
+	pub fn transform(&self, m: Mat4) -> Plane {
+		// Normals transform by the inverse-transpose of the upper-left
+		// 3x3, so that they stay perpendicular to the plane.  The adjugate
+		// is the inverse times the determinant, and works for singular
+		// matrices too: only the direction matters, so undo a flip.
+		let adjugate = m.adjugate();
+		let sign = if m.determinant() < 0.0 { -1.0 } else { 1.0 };
+		let n = self.facing;
+		let normal = |c: usize| {
+			let c = adjugate.column(c);
+			(c.x * n.x + c.y * n.y + c.z * n.z) * sign
+		};
+		let facing = Vec3::new(normal(0), normal(1), normal(2)).normalize();
+		// A point on the transformed plane.
+		let point = m * (self.facing * self.offset);
+
+		Plane { facing, offset: facing.dot(point) }
+	}
+
+	/// Transform the plane by a matrix, or `None` if `m` isn't invertible.
+	pub fn try_transform(&self, m: Mat4) -> Option<Plane> {
+		m.inverse()?;
+
+		Some(self.transform(m))
+	}
+
+	/// Get the signed distance from the Plane to point `p` (positive on the
+	/// side that the plane is facing).
+	pub fn distance_to_point(&self, p: Vec3) -> f32 {
+		self.facing.dot(p) - self.offset
+	}
+
+	/// Get the signed distances from the Plane to the nearest (most
+	/// negative) and farthest (most positive) corners of `bbox`.  The box
+	/// is fully in front if both are positive, fully behind if both are
+	/// negative, and intersects the plane otherwise.
+	pub fn distance_to_bbox(&self, bbox: BBox) -> (f32, f32) {
+		let (near, far) = bbox.pn_pair_from_normal(self.facing);
+
+		(self.distance_to_point(near), self.distance_to_point(far))
+	}
+
+	/// Find where `seg` crosses the Plane.  Returns `None` if both ends are
+	/// on the same side, or if `seg` is parallel to the Plane.
+	pub fn intersect_segment(&self, seg: Segment) -> Option<Vec3> {
+		let a = self.distance_to_point(seg.start);
+		let b = self.distance_to_point(seg.end);
+
+		if a == b || (a > 0.0 && b > 0.0) || (a < 0.0 && b < 0.0) {
+			return None;
+		}
+
+		Some(seg.start + (seg.end - seg.start) * (a / (a - b)))
+	}
+
+	/// Find the point where the Plane, `a` and `b` all meet.  See
+	/// `triple_plane_intersection()`.
+	pub fn intersect_with_two_planes(&self, a: Plane, b: Plane)
+		-> Option<Vec3>
+	{
+		triple_plane_intersection(*self, a, b)
+	}
+
 	/// Returns true if distance from Plane to point is positive.
 	pub fn isdistpos_point(&self, p: Vec3) -> bool {
 		(self.facing.x * (p.x - (self.facing.x * self.offset)))
@@ -72,6 +161,133 @@ impl Plane {
 	}
 }
 
+/// Find the single point where 3 planes meet.  Returns `None` if two of them
+/// are parallel, or if they all meet along a line.
+pub fn triple_plane_intersection(p1: Plane, p2: Plane, p3: Plane)
+	-> Option<Vec3>
+{
+	let (n1, n2, n3) = (p1.facing, p2.facing, p3.facing);
+	let n2n3 = n2.cross(n3);
+	let det = n1.dot(n2n3);
+
+	if det.abs() <= ::std::f32::EPSILON {
+		return None;
+	}
+
+	Some((n2n3 * p1.offset + n3.cross(n1) * p2.offset
+		+ n1.cross(n2) * p3.offset) / det)
+}
+
+#[test]
+fn test_plane_flip() {
+	let a = Plane::new(Vec3::new(1.0, 2.0, -0.5), 3.0);
+	let p = Vec3::new(5.0, 5.0, 0.0);
+	assert!(a.flip().flip() == a);
+	assert!(a.isdistpos_point(p));
+	assert!(!a.flip().isdistpos_point(p));
+	assert!(a.flip().distance_to_point(p) == -a.distance_to_point(p));
+	assert!(a.signed_distance_to_origin() == -a.distance_to_point(Vec3::zero()));
+}
+
+#[test]
+fn test_plane_transform() {
+	let a = Plane::new(Vec3::new(1.0, 2.0, -0.5), 3.0);
+	// Two directions along the plane, and a point on it.
+	let u = a.facing.cross(Vec3::new(0.0, 0.0, 1.0)).normalize();
+	let v = a.facing.cross(u);
+	let p = a.facing * a.offset;
+
+	let matrices = [
+		::IDENTITY.translate(1.0, -2.0, 3.0),
+		::IDENTITY.rotate(0.25, 0.5, 0.1).translate(1.0, -2.0, 3.0),
+		::IDENTITY.scale(1.0, 3.0, 0.5).translate(0.0, 2.0, 0.0),
+		// Mirror
+		::IDENTITY.scale(-1.0, 2.0, 1.0),
+		// Shear
+		Mat4([
+			1.0, 0.0, 0.0, 0.0,
+			0.5, 1.0, 0.0, 0.0,
+			0.0, -2.0, 1.0, 0.0,
+			0.0, 0.0, 0.0, 1.0,
+		]),
+	];
+
+	for m in matrices.iter() {
+		let b = *m * a;
+		assert!((b.facing.mag() - 1.0).abs() < 0.0001);
+		for q in [p, p + u * 5.0, p - v * 2.0, p + u + v].iter() {
+			assert!(b.distance_to_point(*m * *q).abs() < 0.0001);
+		}
+		// Front stays in front
+		assert!(b.distance_to_point(*m * (p + a.facing)) > 0.0);
+	}
+}
+
+#[test]
+fn test_plane_transform_singular() {
+	let a = Plane::new(Vec3::new(1.0, 2.0, -0.5), 3.0);
+	let flatten = ::IDENTITY.scale(1.0, 0.0, 1.0);
+	assert!(a.try_transform(flatten).is_none());
+	let moved = ::IDENTITY.translate(0.0, 1.0, 0.0);
+	assert!(a.try_transform(moved) == Some(moved * a));
+
+	// Everything is flattened onto y = 0, in front of which is +Y.
+	let b = flatten * a;
+	assert!(b == a.transform(flatten));
+	assert!(b.facing == Vec3::new(0.0, 1.0, 0.0) && b.offset == 0.0);
+}
+
+#[test]
+fn test_plane_triple_intersection() {
+	let x = Plane::new(Vec3::new(1.0, 0.0, 0.0), 0.0);
+	let y = Plane::new(Vec3::new(0.0, 1.0, 0.0), 0.0);
+	let z = Plane::new(Vec3::new(0.0, 0.0, -1.0), 0.0);
+	assert!(triple_plane_intersection(x, y, z) == Some(Vec3::zero()));
+
+	let a = Plane::new(Vec3::new(1.0, 1.0, 0.0), 2.0);
+	let b = Plane::new(Vec3::new(0.0, 1.0, -2.0), -1.0);
+	let c = Plane::new(Vec3::new(-0.5, 0.0, 1.0), 3.0);
+	let p = a.intersect_with_two_planes(b, c).unwrap();
+	for plane in [a, b, c].iter() {
+		assert!(plane.distance_to_point(p).abs() < 0.0001);
+	}
+
+	// Parallel, and meeting along the Z axis.
+	assert!(triple_plane_intersection(x, x.flip(), y).is_none());
+	let xy = Plane::new(Vec3::new(1.0, 1.0, 0.0), 0.0);
+	assert!(triple_plane_intersection(x, y, xy).is_none());
+}
+
+#[test]
+fn test_plane_distance_to_bbox() {
+	let a = Plane::new(Vec3::new(0.0, -1.0, 0.0), 2.0); // y = -2
+	let bbox = |y: f32| BBox::new(Vec3::new(-1.0, y, 4.0),
+		Vec3::new(1.0, y + 1.0, 5.0));
+
+	// In front (below), behind (above), and straddling
+	assert!(a.distance_to_bbox(bbox(-5.0)) == (2.0, 3.0));
+	assert!(a.distance_to_bbox(bbox(1.0)) == (-4.0, -3.0));
+	assert!(a.distance_to_bbox(bbox(-2.5)) == (-0.5, 0.5));
+}
+
+#[test]
+fn test_plane_intersect_segment() {
+	let a = Plane::new(Vec3::new(0.0, 0.0, 1.0), 2.0); // z = 2
+	let seg = |z1: f32, z2: f32| {
+		Segment::new(Vec3::new(1.0, 1.0, z1), Vec3::new(3.0, -1.0, z2))
+	};
+	let hit = |z1: f32, z2: f32| a.intersect_segment(seg(z1, z2));
+
+	assert!(hit(0.0, 4.0) == Some(Vec3::new(2.0, 0.0, 2.0)));
+	assert!(hit(4.0, 0.0) == Some(Vec3::new(2.0, 0.0, 2.0)));
+	// Same side, and parallel
+	assert!(hit(3.0, 5.0).is_none());
+	assert!(hit(1.0, 1.0).is_none());
+	// One end on the plane
+	assert!(hit(2.0, 5.0) == Some(Vec3::new(1.0, 1.0, 2.0)));
+	assert!(hit(-1.0, 2.0) == Some(Vec3::new(3.0, -1.0, 2.0)));
+}
+
 /*#[test]
 fn test_plane_distpos() {
 	let t = ::Transform::new()
@@ -0,0 +1,505 @@
+// "ami" crate - Licensed under the MIT LICENSE
+//  * Copyright (c) 2017-2018  Jeron A. Lau <jeron.lau@plopgrizzly.com>
+
+use std::fmt;
+use std::ops;
+
+use Vec3;
+use Mat3;
+
+/// A quaternion, used to represent rotations.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Quaternion {
+	/// I (X) coordinate
+	pub x: f32,
+	/// J (Y) coordinate
+	pub y: f32,
+	/// K (Z) coordinate
+	pub z: f32,
+	/// Real (W) coordinate
+	pub w: f32,
+}
+
+impl fmt::Debug for Quaternion {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "({:?},{:?},{:?},{:?})", self.x, self.y, self.z, self.w)
+	}
+}
+
+impl fmt::Display for Quaternion {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "({}i + {}j + {}k + {})", self.x, self.y, self.z, self.w)
+	}
+}
+
+impl ops::Add for Quaternion {
+	type Output = Quaternion;
+
+	fn add(self, other: Quaternion) -> Self::Output {
+		Quaternion::new(self.x + other.x, self.y + other.y,
+			self.z + other.z, self.w + other.w)
+	}
+}
+
+impl ops::Neg for Quaternion {
+	type Output = Quaternion;
+
+	fn neg(self) -> Self::Output {
+		Quaternion::new(-self.x, -self.y, -self.z, -self.w)
+	}
+}
+
+impl ops::Mul for Quaternion {
+	type Output = Quaternion;
+
+	/// Calculate the Hamilton product (combine rotations, `rhs` first).
+	fn mul(self, rhs: Quaternion) -> Self::Output {
+		let (a, b) = (self, rhs);
+
+		Quaternion::new(
+			a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+			a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+			a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+			a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+		)
+	}
+}
+
+impl ops::Mul<Vec3> for Quaternion {
+	type Output = Vec3;
+
+	/// Rotate a point or direction.
+	fn mul(self, rhs: Vec3) -> Self::Output {
+		self.mul_vec3(rhs)
+	}
+}
+
+impl ops::Mul<f32> for Quaternion {
+	type Output = Quaternion;
+
+	fn mul(self, s: f32) -> Self::Output {
+		Quaternion::new(self.x * s, self.y * s, self.z * s, self.w * s)
+	}
+}
+
+impl ops::Div<f32> for Quaternion {
+	type Output = Quaternion;
+
+	fn div(self, s: f32) -> Self::Output {
+		Quaternion::new(self.x / s, self.y / s, self.z / s, self.w / s)
+	}
+}
+
+impl Quaternion {
+	/// Create a new Quaternion
+	pub fn new(x: f32, y: f32, z: f32, w: f32) -> Quaternion {
+		Quaternion { x, y, z, w }
+	}
+
+	/// Create a Quaternion that doesn't rotate.
+	pub fn identity() -> Quaternion {
+		Quaternion::new(0.0, 0.0, 0.0, 1.0)
+	}
+
+	/// Create a Quaternion that rotates `angle` radians around `axis`
+	/// (counter-clockwise when looking from the tip of `axis`).
+	pub fn from_axis_angle(axis: Vec3, angle: f32) -> Quaternion {
+		let axis = axis.normalize() * (angle / 2.0).sin();
+
+		Quaternion::new(axis.x, axis.y, axis.z, (angle / 2.0).cos())
+	}
+
+	/// Create a Quaternion that rotates `angle` radians around the X axis.
+	pub fn from_rotation_x(angle: f32) -> Quaternion {
+		Self::from_axis_angle(Vec3::x_axis(), angle)
+	}
+
+	/// Create a Quaternion that rotates `angle` radians around the Y axis.
+	pub fn from_rotation_y(angle: f32) -> Quaternion {
+		Self::from_axis_angle(Vec3::y_axis(), angle)
+	}
+
+	/// Create a Quaternion that rotates `angle` radians around the Z axis.
+	pub fn from_rotation_z(angle: f32) -> Quaternion {
+		Self::from_axis_angle(Vec3::z_axis(), angle)
+	}
+
+	/// Get the magnitude squared of a Quaternion
+	pub fn magnitude_squared(self) -> f32 {
+		self.x * self.x + self.y * self.y + self.z * self.z
+			+ self.w * self.w
+	}
+
+	/// Get the magnitude of a Quaternion
+	pub fn magnitude(self) -> f32 {
+		self.magnitude_squared().sqrt()
+	}
+
+	/// Normalize a Quaternion, so that it's a valid rotation.  A zero
+	/// Quaternion becomes the identity.
+	pub fn normalize(self) -> Quaternion {
+		let m = self.magnitude();
+		if m > 0.0 {
+			self / m
+		} else {
+			Quaternion::identity()
+		}
+	}
+
+	/// Get the conjugate of a Quaternion (the opposite rotation, if it's
+	/// normalized).
+	pub fn conjugate(self) -> Quaternion {
+		Quaternion::new(-self.x, -self.y, -self.z, self.w)
+	}
+
+	/// Calculate the dot product of two `Quaternion`s
+	pub fn dot(self, other: Quaternion) -> f32 {
+		self.x * other.x + self.y * other.y + self.z * other.z
+			+ self.w * other.w
+	}
+
+	/// Get the angle (radians) between the rotations of two normalized
+	/// `Quaternion`s.
+	pub fn angle_between(self, other: Quaternion) -> f32 {
+		2.0 * self.dot(other).abs().min(1.0).acos()
+	}
+
+	/// Calculate normalized linear interpolation (nlerp) of two normalized
+	/// `Quaternion`s, the short way around.  It's faster than slerp, but
+	/// doesn't rotate at a constant speed.
+	pub fn nlerp(self, other: Quaternion, t: f32) -> Quaternion {
+		let other = if self.dot(other) < 0.0 { -other } else { other };
+
+		(self * (1.0 - t) + other * t).normalize()
+	}
+
+	/// Calculate spherical linear interpolation (slerp) of two normalized
+	/// `Quaternion`s, the short way around.  Unlike `nlerp()`, it rotates
+	/// at a constant speed.
+	pub fn slerp(self, other: Quaternion, t: f32) -> Quaternion {
+		let mut cos = self.dot(other);
+		let mut other = other;
+
+		if cos < 0.0 {
+			cos = -cos;
+			other = -other;
+		}
+
+		// Too close to divide by sin, but nlerp is good enough there.
+		if cos > 0.9995 {
+			return self.nlerp(other, t);
+		}
+
+		let angle = cos.acos();
+		let sin = angle.sin();
+
+		self * (((1.0 - t) * angle).sin() / sin)
+			+ other * ((t * angle).sin() / sin)
+	}
+
+	/// Limit a normalized `Quaternion` to be at most `max_angle` radians
+	/// away from `reference` (for joint limits in inverse kinematics).  A
+	/// rotation too far away is slerped back to the edge of the cone.
+	pub fn constrain_to_cone(self, reference: Quaternion, max_angle: f32)
+		-> Quaternion
+	{
+		let angle = reference.angle_between(self);
+
+		if angle <= max_angle {
+			return self;
+		}
+
+		reference.slerp(self, max_angle / angle)
+	}
+
+	/// Calculate spherical quadrangle interpolation (squad) from `q0` (`t`
+	/// = 0) to `q1` (`t` = 1), curving towards the control points `s0` and
+	/// `s1`.  Used to get smooth curves through a sequence of rotations.
+	pub fn squad(q0: Quaternion, q1: Quaternion, s0: Quaternion,
+		s1: Quaternion, t: f32) -> Quaternion
+	{
+		q0.slerp(q1, t).slerp(s0.slerp(s1, t), 2.0 * t * (1.0 - t))
+	}
+
+	/// Calculate the inner control point for keyframe `q` of a `squad()`
+	/// curve, from the keyframes before and after it.  Do this once per
+	/// keyframe, not every frame.  For the first and last keyframes, pass
+	/// the keyframe itself as the missing neighbor.
+	pub fn squad_setup(q_prev: Quaternion, q: Quaternion,
+		q_next: Quaternion) -> Quaternion
+	{
+		// Go the short way around to each neighbor.
+		let q_prev = if q.dot(q_prev) < 0.0 { -q_prev } else { q_prev };
+		let q_next = if q.dot(q_next) < 0.0 { -q_next } else { q_next };
+		let inv = q.conjugate();
+
+		let tangent = ((inv * q_next).ln() + (inv * q_prev).ln()) * -0.25;
+
+		(q * tangent.exp()).normalize()
+	}
+
+	/// Natural logarithm of a normalized Quaternion (W is 0).
+	fn ln(self) -> Quaternion {
+		let angle = self.w.max(-1.0).min(1.0).acos();
+		let sin = angle.sin();
+
+		if sin.abs() <= ::std::f32::EPSILON {
+			return Quaternion::new(0.0, 0.0, 0.0, 0.0);
+		}
+
+		let k = angle / sin;
+		Quaternion::new(self.x * k, self.y * k, self.z * k, 0.0)
+	}
+
+	/// Exponential of a Quaternion with W = 0 (opposite of `ln()`).
+	fn exp(self) -> Quaternion {
+		let angle = Vec3::new(self.x, self.y, self.z).mag();
+
+		if angle <= ::std::f32::EPSILON {
+			return Quaternion::identity();
+		}
+
+		let k = angle.sin() / angle;
+		Quaternion::new(self.x * k, self.y * k, self.z * k, angle.cos())
+	}
+
+	/// Create the rotation that turns +Z to face `forward`, with +Y turned
+	/// as close to `up` as possible.  `forward` and `up` must not be
+	/// parallel.
+	pub fn look_rotation(forward: Vec3, up: Vec3) -> Quaternion {
+		let f = forward.normalize();
+		let r = up.cross(f).normalize();
+		let u = f.cross(r);
+
+		Quaternion::from_mat3(Mat3([
+			r.x, r.y, r.z,
+			u.x, u.y, u.z,
+			f.x, f.y, f.z,
+		]))
+	}
+
+	/// Create a `Quaternion` from a rotation matrix (Shepperd's method).
+	/// The result is normalized, so a little scale in `m` is ok.
+	pub fn from_mat3(m: Mat3) -> Quaternion {
+		// Element at `c`olumn, `r`ow.
+		let e = |c: usize, r: usize| m.0[c * 3 + r];
+		// Pick the largest of w, x, y and z to divide by, for stability.
+		let trace = e(0, 0) + e(1, 1) + e(2, 2);
+
+		let q = if trace > 0.0 {
+			let s = (trace + 1.0).sqrt() * 2.0; // 4w
+			Quaternion::new((e(1, 2) - e(2, 1)) / s,
+				(e(2, 0) - e(0, 2)) / s,
+				(e(0, 1) - e(1, 0)) / s, s / 4.0)
+		} else if e(0, 0) > e(1, 1) && e(0, 0) > e(2, 2) {
+			let s = (1.0 + e(0, 0) - e(1, 1) - e(2, 2)).sqrt() * 2.0; // 4x
+			Quaternion::new(s / 4.0, (e(1, 0) + e(0, 1)) / s,
+				(e(2, 0) + e(0, 2)) / s,
+				(e(1, 2) - e(2, 1)) / s)
+		} else if e(1, 1) > e(2, 2) {
+			let s = (1.0 + e(1, 1) - e(0, 0) - e(2, 2)).sqrt() * 2.0; // 4y
+			Quaternion::new((e(1, 0) + e(0, 1)) / s, s / 4.0,
+				(e(2, 1) + e(1, 2)) / s,
+				(e(2, 0) - e(0, 2)) / s)
+		} else {
+			let s = (1.0 + e(2, 2) - e(0, 0) - e(1, 1)).sqrt() * 2.0; // 4z
+			Quaternion::new((e(2, 0) + e(0, 2)) / s,
+				(e(2, 1) + e(1, 2)) / s, s / 4.0,
+				(e(0, 1) - e(1, 0)) / s)
+		};
+
+		q.normalize()
+	}
+
+	/// Convert a normalized Quaternion into a rotation matrix.
+	pub fn to_mat3(self) -> Mat3 {
+		let Quaternion { x, y, z, w } = self;
+
+		Mat3([
+			1.0 - 2.0 * (y * y + z * z),
+			2.0 * (x * y + z * w),
+			2.0 * (x * z - y * w),
+
+			2.0 * (x * y - z * w),
+			1.0 - 2.0 * (x * x + z * z),
+			2.0 * (y * z + x * w),
+
+			2.0 * (x * z + y * w),
+			2.0 * (y * z - x * w),
+			1.0 - 2.0 * (x * x + y * y),
+		])
+	}
+
+	/// Rotate a point or direction by a normalized Quaternion.
+	pub fn mul_vec3(self, v: Vec3) -> Vec3 {
+		let p = self * Quaternion::new(v.x, v.y, v.z, 0.0)
+			* self.conjugate();
+
+		Vec3::new(p.x, p.y, p.z)
+	}
+
+	/// Get the inverse of a Quaternion.  For normalized quaternions this
+	/// is the same as `conjugate()`, which is faster.
+	pub fn inverse(self) -> Quaternion {
+		self.conjugate() / self.magnitude_squared()
+	}
+}
+
+#[test]
+fn test_quaternion_inverse() {
+	let q = Quaternion::new(1.0, -2.0, 0.5, 3.0);
+	let i = q * q.inverse();
+	assert!((i.x.abs() + i.y.abs() + i.z.abs() + (i.w - 1.0).abs()) < 0.0001);
+	assert!((q.normalize().magnitude() - 1.0).abs() < 0.0001);
+	let n = q.normalize();
+	assert!((n.inverse().x - n.conjugate().x).abs() < 0.0001);
+	assert_eq!(format!("{}", q), "(1i + -2j + 0.5k + 3)");
+}
+
+#[test]
+fn test_quaternion_angle_between() {
+	let q = Quaternion::new(0.5, 0.5, 0.5, 0.5);
+	assert!(q.angle_between(q) == 0.0);
+	// -q is the same rotation.
+	assert!(q.angle_between(-q) == 0.0);
+	// 180° rotation around the X axis.
+	let x = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+	assert!((Quaternion::identity().angle_between(x)
+		- ::std::f32::consts::PI).abs() < 0.0001);
+}
+
+#[test]
+fn test_quaternion_mul_vec3() {
+	let pi = ::std::f32::consts::PI;
+	let (x, y, z) = (Vec3::x_axis(), Vec3::y_axis(), Vec3::z_axis());
+	assert!(Quaternion::from_rotation_x(pi / 2.0).mul_vec3(y).dist(z) < 0.0001);
+	assert!((Quaternion::from_rotation_y(pi / 2.0) * z).dist(x) < 0.0001);
+	assert!((Quaternion::from_rotation_z(pi / 2.0) * x).dist(y) < 0.0001);
+	assert!(Quaternion::identity() * x == x);
+}
+
+#[test]
+fn test_quaternion_nlerp() {
+	let a = Quaternion::from_rotation_x(0.5);
+	let b = Quaternion::from_rotation_y(2.0);
+	for i in 0..=10 {
+		let t = i as f32 / 10.0;
+		assert!(a.nlerp(a, t).angle_between(a) < 0.001);
+		assert!((a.nlerp(b, t).magnitude() - 1.0).abs() < 0.0001);
+		// -b is the same rotation as b.
+		assert!(a.nlerp(-b, t).angle_between(a.nlerp(b, t)) < 0.001);
+	}
+	assert!(a.nlerp(b, 1.0).angle_between(b) < 0.001);
+}
+
+#[test]
+fn test_quaternion_slerp() {
+	let a = Quaternion::from_rotation_x(0.5);
+	let b = Quaternion::from_rotation_y(2.0);
+	let angle = a.angle_between(b);
+	for i in 0..=10 {
+		let t = i as f32 / 10.0;
+		// Constant speed
+		let q = a.slerp(b, t);
+		assert!((q.magnitude() - 1.0).abs() < 0.0001);
+		assert!((q.angle_between(a) - angle * t).abs() < 0.001);
+		assert!(a.slerp(-b, t).angle_between(q) < 0.001);
+	}
+	assert!(a.slerp(a, 0.5).angle_between(a) < 0.001);
+}
+
+#[test]
+fn test_quaternion_squad() {
+	let q0 = Quaternion::from_rotation_x(0.5);
+	let q1 = Quaternion::from_rotation_y(2.0);
+	let s0 = Quaternion::from_rotation_z(1.0);
+	let s1 = Quaternion::from_axis_angle(Vec3::new(1.0, 1.0, 0.0), -1.0);
+
+	assert!(Quaternion::squad(q0, q1, s0, s1, 0.0).angle_between(q0)
+		< 0.001);
+	assert!(Quaternion::squad(q0, q1, s0, s1, 1.0).angle_between(q1)
+		< 0.001);
+	// Close to the ends just inside
+	assert!(Quaternion::squad(q0, q1, s0, s1, 0.001).angle_between(q0)
+		< 0.01);
+	assert!(Quaternion::squad(q0, q1, s0, s1, 0.999).angle_between(q1)
+		< 0.01);
+}
+
+#[test]
+fn test_quaternion_squad_setup() {
+	let q0 = Quaternion::from_rotation_x(0.5);
+	let q1 = Quaternion::from_rotation_y(2.0);
+	let q2 = Quaternion::from_rotation_z(1.0);
+
+	// An end keyframe with no neighbors is its own control point.
+	assert!(Quaternion::squad_setup(q0, q0, q0).angle_between(q0) < 0.001);
+	// In the middle of a constant rotation it is too.
+	let step = Quaternion::from_rotation_x(0.3);
+	let s = Quaternion::squad_setup(q0, step * q0, step * step * q0);
+	assert!(s.angle_between(step * q0) < 0.001);
+
+	let s0 = Quaternion::squad_setup(q0, q0, q1);
+	let s1 = Quaternion::squad_setup(q0, q1, q2);
+	assert!((s1.magnitude() - 1.0).abs() < 0.0001);
+	assert!(Quaternion::squad(q0, q1, s0, s1, 0.0).angle_between(q0)
+		< 0.001);
+	assert!(Quaternion::squad(q0, q1, s0, s1, 1.0).angle_between(q1)
+		< 0.001);
+}
+
+#[test]
+fn test_quaternion_mat3() {
+	assert!(Quaternion::identity().to_mat3() == Mat3::identity());
+	assert!(Quaternion::from_mat3(Mat3::identity()) == Quaternion::identity());
+
+	let v = Vec3::new(0.5, -1.0, 2.0);
+	// Each branch of Shepperd's method: w, x, y and z largest.
+	for q in [Quaternion::new(0.1, 0.2, 0.3, 0.9),
+		Quaternion::new(0.9, 0.2, -0.3, 0.1),
+		Quaternion::new(-0.2, 0.9, 0.3, 0.1),
+		Quaternion::new(0.3, 0.2, 0.9, -0.1)].iter()
+	{
+		let q = q.normalize();
+		let m = q.to_mat3();
+		assert!((m * v).dist(q * v) < 0.0001);
+		let back = Quaternion::from_mat3(m);
+		assert!((back.magnitude() - 1.0).abs() < 0.0001);
+		assert!(back.angle_between(q) < 0.001);
+	}
+}
+
+#[test]
+fn test_quaternion_look_rotation() {
+	let (x, y, z) = (Vec3::x_axis(), Vec3::y_axis(), Vec3::z_axis());
+	assert!(Quaternion::look_rotation(z, y) == Quaternion::identity());
+
+	let forward = Vec3::new(1.0, 2.0, -3.0);
+	let up = Vec3::new(0.0, 1.0, 0.5);
+	let q = Quaternion::look_rotation(forward, up);
+	assert!((q * z).dist(forward.normalize()) < 0.0001);
+	// Up is perpendicular to forward, on the same side as `up`.
+	assert!((q * y).dot(forward).abs() < 0.0001);
+	assert!((q * y).dot(up) > 0.0);
+	assert!((q * x).dot(up).abs() < 0.0001);
+}
+
+#[test]
+fn test_quaternion_constrain_to_cone() {
+	let reference = Quaternion::from_rotation_y(0.3);
+	let axis = Vec3::new(1.0, 2.0, -0.5);
+
+	// Within the cone.
+	let inside = reference * Quaternion::from_axis_angle(axis, 0.4);
+	assert!(inside.constrain_to_cone(reference, 0.5) == inside);
+
+	// Outside: brought to the edge, along the way to `self`.
+	let outside = reference * Quaternion::from_axis_angle(axis, 1.2);
+	let limited = outside.constrain_to_cone(reference, 0.5);
+	assert!((limited.angle_between(reference) - 0.5).abs() < 0.001);
+	assert!((limited.angle_between(outside) - 0.7).abs() < 0.001);
+	assert!((limited.magnitude() - 1.0).abs() < 0.0001);
+	// Same rotation with the opposite sign.
+	let limited = (-outside).constrain_to_cone(reference, 0.5);
+	assert!((limited.angle_between(reference) - 0.5).abs() < 0.001);
+}
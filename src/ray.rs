@@ -0,0 +1,223 @@
+// "ami" crate - Licensed under the MIT LICENSE
+//  * Copyright (c) 2017-2018  Jeron A. Lau <jeron.lau@plopgrizzly.com>
+
+use std::fmt;
+
+use Vec3;
+use BBox;
+use Sphere;
+
+/// A ray (half-line) that starts at `origin`.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Ray {
+	/// Where the ray starts
+	pub origin: Vec3,
+	/// A normalized directional vector for the direction the ray goes.
+	pub direction: Vec3,
+}
+
+impl fmt::Debug for Ray {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:?} → {:?}", self.origin, self.direction)
+	}
+}
+
+impl Ray {
+	/// Create a new ray from an origin and directional vector.
+	pub fn new(origin: Vec3, dir: Vec3) -> Ray {
+		Ray { origin, direction: dir.normalize() }
+	}
+
+	/// Get the point `t` units along the ray.  Negative `t` gives points
+	/// behind `origin`, on the line but not the ray; use `at_clamp()` if
+	/// that's not wanted.
+	pub fn at(self, t: f32) -> Vec3 {
+		self.origin + self.direction * t
+	}
+
+	/// Get the point `t` units along the ray, or `origin` if `t` is
+	/// negative, so that it's always on the ray.
+	pub fn at_clamp(self, t: f32) -> Vec3 {
+		self.at(t.max(0.0))
+	}
+
+	/// Check if the point `t` units along the ray is in front of (or at)
+	/// `origin`.
+	pub fn is_in_front(self, t: f32) -> bool {
+		t >= 0.0
+	}
+
+	/// Get the distances along the ray to where it enters and exits `bbox`,
+	/// or `None` if it misses.  The entry distance is 0 if the ray starts
+	/// inside of `bbox`.
+	pub fn intersects_bbox(self, bbox: BBox) -> Option<(f32, f32)> {
+		let slab = |min: f32, max: f32, o: f32, dir: f32| {
+			// Parallel to the slab: all in or all out of it.
+			if dir == 0.0 {
+				return if o >= min && o <= max {
+					(::std::f32::NEG_INFINITY, ::std::f32::INFINITY)
+				} else {
+					(::std::f32::INFINITY, ::std::f32::NEG_INFINITY)
+				};
+			}
+
+			let inv = 1.0 / dir;
+			let (a, b) = ((min - o) * inv, (max - o) * inv);
+			(a.min(b), a.max(b))
+		};
+
+		let (x0, x1) = slab(bbox.min.x, bbox.max.x, self.origin.x,
+			self.direction.x);
+		let (y0, y1) = slab(bbox.min.y, bbox.max.y, self.origin.y,
+			self.direction.y);
+		let (z0, z1) = slab(bbox.min.z, bbox.max.z, self.origin.z,
+			self.direction.z);
+
+		let enter = x0.max(y0).max(z0).max(0.0);
+		let exit = x1.min(y1).min(z1);
+
+		if enter <= exit {
+			Some((enter, exit))
+		} else {
+			None
+		}
+	}
+
+	/// Get the distances along the ray to where it enters and exits
+	/// `sphere`, or `None` if it misses.  The entry distance is 0 if the ray
+	/// starts inside of `sphere`.
+	pub fn intersects_sphere(self, sphere: Sphere) -> Option<(f32, f32)> {
+		// Distance along the ray to the point closest to the center.
+		let to_center = sphere.center - self.origin;
+		let closest = to_center.dot(self.direction);
+		// Squared distance from there to the center.
+		let miss = to_center - self.direction * closest;
+		let miss = miss.dot(miss);
+		let radius2 = sphere.radius * sphere.radius;
+
+		if miss > radius2 {
+			return None;
+		}
+
+		let half_chord = (radius2 - miss).sqrt();
+		let exit = closest + half_chord;
+
+		if exit < 0.0 {
+			return None;
+		}
+
+		Some(((closest - half_chord).max(0.0), exit))
+	}
+}
+
+/// Find the closest point on each ray to the other, and the distance between
+/// them.  Returns `None` if the rays are parallel.
+pub fn closest_points_between_rays(r1: Ray, r2: Ray)
+	-> Option<(Vec3, Vec3, f32)>
+{
+	let r = r1.origin - r2.origin;
+	let a = r1.direction.dot(r1.direction);
+	let b = r1.direction.dot(r2.direction);
+	let c = r1.direction.dot(r);
+	let e = r2.direction.dot(r2.direction);
+	let f = r2.direction.dot(r);
+	let denom = a * e - b * b;
+
+	if denom <= ::std::f32::EPSILON * a * e {
+		return None;
+	}
+
+	// Closest points of the infinite lines, clamped to the rays.
+	let mut s = ((b * f - c * e) / denom).max(0.0);
+	let mut t = (b * s + f) / e;
+
+	if t < 0.0 {
+		t = 0.0;
+		s = (-c / a).max(0.0);
+	}
+
+	let p1 = r1.at(s);
+	let p2 = r2.at(t);
+
+	Some((p1, p2, p1.dist(p2)))
+}
+
+#[test]
+fn test_closest_points_between_rays() {
+	let x = Ray::new(Vec3::zero(), Vec3::new(1.0, 0.0, 0.0));
+
+	// Parallel
+	let a = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(-2.0, 0.0, 0.0));
+	assert!(closest_points_between_rays(x, a).is_none());
+	// Perpendicular
+	let b = Ray::new(Vec3::new(2.0, 1.0, -3.0), Vec3::new(0.0, 0.0, 1.0));
+	let (p1, p2, d) = closest_points_between_rays(x, b).unwrap();
+	assert!(p1 == Vec3::new(2.0, 0.0, 0.0));
+	assert!(p2 == Vec3::new(2.0, 1.0, 0.0));
+	assert!(d == 1.0);
+	// Intersecting
+	let c = Ray::new(Vec3::new(3.0, -2.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+	let (p1, p2, d) = closest_points_between_rays(x, c).unwrap();
+	assert!(p1 == Vec3::new(3.0, 0.0, 0.0) && p1 == p2 && d == 0.0);
+	// Lines intersect behind the origin of one of the rays
+	let e = Ray::new(Vec3::new(3.0, -2.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+	let (p1, p2, d) = closest_points_between_rays(x, e).unwrap();
+	assert!(p1 == Vec3::new(3.0, 0.0, 0.0));
+	assert!(p2 == Vec3::new(3.0, -2.0, 0.0));
+	assert!(d == 2.0);
+}
+
+#[test]
+fn test_ray_at_clamp() {
+	let ray = Ray::new(Vec3::new(1.0, 2.0, 3.0), Vec3::new(0.0, 2.0, 0.0));
+	assert!(ray.at(-2.0) == Vec3::new(1.0, 0.0, 3.0));
+	assert!(ray.at_clamp(-2.0) == ray.origin);
+	assert!(ray.at_clamp(2.0) == ray.at(2.0));
+	assert!(!ray.is_in_front(-0.5));
+	assert!(ray.is_in_front(0.0) && ray.is_in_front(3.0));
+}
+
+#[test]
+fn test_ray_intersects_bbox() {
+	let bbox = BBox::new(Vec3::new(1.0, -1.0, -1.0), Vec3::new(3.0, 1.0, 1.0));
+
+	let ray = Ray::new(Vec3::zero(), Vec3::new(1.0, 0.0, 0.0));
+	assert!(ray.intersects_bbox(bbox) == Some((1.0, 3.0)));
+	// Starting inside
+	let ray = Ray::new(Vec3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+	assert!(ray.intersects_bbox(bbox) == Some((0.0, 1.0)));
+	// Pointing away, and missing to the side
+	let ray = Ray::new(Vec3::zero(), Vec3::new(-1.0, 0.0, 0.0));
+	assert!(ray.intersects_bbox(bbox).is_none());
+	let ray = Ray::new(Vec3::new(0.0, 2.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+	assert!(ray.intersects_bbox(bbox).is_none());
+
+	// Grazing along a face, and just outside of it.
+	let cube = BBox::new(Vec3::zero(), Vec3::one());
+	let ray = Ray::new(Vec3::new(-1.0, 0.0, 0.5), Vec3::new(1.0, 0.0, 0.0));
+	assert!(ray.intersects_bbox(cube) == Some((1.0, 2.0)));
+	let ray = Ray::new(Vec3::new(-1.0, 1.0, 1.0), Vec3::new(1.0, 0.0, 0.0));
+	assert!(ray.intersects_bbox(cube) == Some((1.0, 2.0)));
+	let ray = Ray::new(Vec3::new(-1.0, -0.001, 0.5),
+		Vec3::new(1.0, 0.0, 0.0));
+	assert!(ray.intersects_bbox(cube).is_none());
+}
+
+#[test]
+fn test_ray_intersects_sphere() {
+	let sphere = Sphere::new(Vec3::new(0.0, 0.0, 5.0), 1.0);
+	let z = Vec3::new(0.0, 0.0, 1.0);
+
+	// Head-on, tangent, and starting inside
+	assert!(Ray::new(Vec3::zero(), z).intersects_sphere(sphere)
+		== Some((4.0, 6.0)));
+	assert!(Ray::new(Vec3::new(0.0, 1.0, 0.0), z).intersects_sphere(sphere)
+		== Some((5.0, 5.0)));
+	assert!(Ray::new(Vec3::new(0.0, 0.0, 5.5), z).intersects_sphere(sphere)
+		== Some((0.0, 0.5)));
+	// Behind the origin, and missing to the side
+	assert!(Ray::new(Vec3::new(0.0, 0.0, 7.0), z).intersects_sphere(sphere)
+		.is_none());
+	assert!(Ray::new(Vec3::new(1.5, 0.0, 0.0), z).intersects_sphere(sphere)
+		.is_none());
+}
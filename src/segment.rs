@@ -0,0 +1,180 @@
+// "ami" crate - Licensed under the MIT LICENSE
+//  * Copyright (c) 2017-2018  Jeron A. Lau <jeron.lau@plopgrizzly.com>
+
+use std::fmt;
+
+use Vec3;
+use Plane;
+
+/// A line segment between two points.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Segment {
+	/// The first end point
+	pub start: Vec3,
+	/// The second end point
+	pub end: Vec3,
+}
+
+impl fmt::Debug for Segment {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:?} — {:?}", self.start, self.end)
+	}
+}
+
+impl Segment {
+	/// Create a new segment between `start` and `end`.
+	pub fn new(start: Vec3, end: Vec3) -> Segment {
+		Segment { start, end }
+	}
+
+	/// Get the point `t` of the way from `start` (0) to `end` (1).  Unlike
+	/// `Ray::at()`, `t` is clamped so the point is always on the segment.
+	pub fn at(self, t: f32) -> Vec3 {
+		self.start.lerp(self.end, t.max(0.0).min(1.0))
+	}
+
+	/// Same as `at()`.
+	pub fn lerp(self, t: f32) -> Vec3 {
+		self.at(t)
+	}
+
+	/// Split the segment where it crosses `plane`, into the part in front
+	/// of the plane and the part behind it.  Either is `None` if there is
+	/// no part on that side.  A segment on the plane counts as in front.
+	pub fn split_at_plane(self, plane: Plane)
+		-> (Option<Segment>, Option<Segment>)
+	{
+		let a = plane.distance_to_point(self.start);
+		let b = plane.distance_to_point(self.end);
+
+		if a >= 0.0 && b >= 0.0 {
+			return (Some(self), None);
+		} else if a <= 0.0 && b <= 0.0 {
+			return (None, Some(self));
+		}
+
+		let p = self.start + (self.end - self.start) * (a / (a - b));
+		let first = Segment::new(self.start, p);
+		let second = Segment::new(p, self.end);
+
+		if a > 0.0 {
+			(Some(first), Some(second))
+		} else {
+			(Some(second), Some(first))
+		}
+	}
+
+	/// Find the closest point on each segment to the other, and the
+	/// distance between them.
+	pub fn closest_points(self, other: Segment) -> (Vec3, Vec3, f32) {
+		let clamp = |x: f32| x.max(0.0).min(1.0);
+		let d1 = self.end - self.start;
+		let d2 = other.end - other.start;
+		let r = self.start - other.start;
+		let a = d1.dot(d1);
+		let e = d2.dot(d2);
+		let f = d2.dot(r);
+
+		// `s` is the position along `self`, `t` along `other`.
+		let (s, t) = if a <= ::std::f32::EPSILON
+			&& e <= ::std::f32::EPSILON
+		{
+			// Both segments are points.
+			(0.0, 0.0)
+		} else if a <= ::std::f32::EPSILON {
+			// `self` is a point.
+			(0.0, clamp(f / e))
+		} else {
+			let c = d1.dot(r);
+
+			if e <= ::std::f32::EPSILON {
+				// `other` is a point.
+				(clamp(-c / a), 0.0)
+			} else {
+				let b = d1.dot(d2);
+				let denom = a * e - b * b;
+				// If parallel, any `s` works: pick `start`.
+				let s = if denom != 0.0 {
+					clamp((b * f - c * e) / denom)
+				} else {
+					0.0
+				};
+				let t = (b * s + f) / e;
+
+				// If `t` is off `other`, clamp it and recompute `s`.
+				if t < 0.0 {
+					(clamp(-c / a), 0.0)
+				} else if t > 1.0 {
+					(clamp((b - c) / a), 1.0)
+				} else {
+					(s, t)
+				}
+			}
+		};
+
+		let p1 = self.start + d1 * s;
+		let p2 = other.start + d2 * t;
+
+		(p1, p2, p1.dist(p2))
+	}
+}
+
+#[test]
+fn test_segment_closest_points() {
+	let a = Segment::new(Vec3::zero(), Vec3::new(4.0, 0.0, 0.0));
+
+	// Parallel
+	let b = Segment::new(Vec3::new(2.0, 1.0, 0.0), Vec3::new(6.0, 1.0, 0.0));
+	assert!(a.closest_points(b).2 == 1.0);
+	// Crossing
+	let c = Segment::new(Vec3::new(1.0, -1.0, 0.0), Vec3::new(1.0, 1.0, 0.0));
+	let (p1, p2, d) = a.closest_points(c);
+	assert!(p1 == Vec3::new(1.0, 0.0, 0.0) && p1 == p2 && d == 0.0);
+	// Skew
+	let e = Segment::new(Vec3::new(3.0, 2.0, -1.0), Vec3::new(3.0, 2.0, 1.0));
+	let (p1, p2, d) = a.closest_points(e);
+	assert!(p1 == Vec3::new(3.0, 0.0, 0.0));
+	assert!(p2 == Vec3::new(3.0, 2.0, 0.0));
+	assert!(d == 2.0);
+	// Closest at the end points
+	let g = Segment::new(Vec3::new(5.0, 1.0, 0.0), Vec3::new(7.0, 3.0, 0.0));
+	let (p1, p2, _) = a.closest_points(g);
+	assert!(p1 == a.end && p2 == g.start);
+	// Touching at the end points
+	let h = Segment::new(Vec3::new(4.0, 0.0, 0.0), Vec3::new(4.0, 5.0, 5.0));
+	assert!(a.closest_points(h).2 == 0.0);
+}
+
+#[test]
+fn test_segment_at() {
+	let s = Segment::new(Vec3::new(1.0, 2.0, 3.0), Vec3::new(3.0, -2.0, 4.0));
+	assert!(s.at(0.0) == s.start && s.at(1.0) == s.end);
+	assert!(s.at(0.5) == s.start.midpoint(s.end));
+	assert!(s.at(-1.0) == s.start && s.at(2.0) == s.end);
+	assert!(s.lerp(0.25) == s.at(0.25));
+}
+
+#[test]
+fn test_segment_split_at_plane() {
+	let plane = Plane::new(Vec3::new(1.0, 1.0, 0.0), 1.0);
+	let s = Segment::new(Vec3::new(3.0, 2.0, 1.0), Vec3::new(-2.0, 0.0, -1.0));
+	let len = s.start.dist(s.end);
+
+	let (front, back) = s.split_at_plane(plane);
+	let (front, back) = (front.unwrap(), back.unwrap());
+	assert!(front.start == s.start && back.end == s.end);
+	assert!(front.end == back.start);
+	assert!(plane.distance_to_point(front.end).abs() < 0.0001);
+	let split_len = front.start.dist(front.end) + back.start.dist(back.end);
+	assert!((split_len - len).abs() < 0.0001);
+
+	// Going the other way, the front part is the end.
+	let r = Segment::new(s.end, s.start);
+	let (front, back) = r.split_at_plane(plane);
+	assert!(front.unwrap().end == r.end && back.unwrap().start == r.start);
+
+	// All on one side.
+	let f = Segment::new(Vec3::new(2.0, 2.0, 0.0), Vec3::new(5.0, 0.0, 0.0));
+	assert!(f.split_at_plane(plane) == (Some(f), None));
+	assert!(f.split_at_plane(plane.flip()) == (None, Some(f)));
+}
@@ -0,0 +1,329 @@
+// "ami" crate - Licensed under the MIT LICENSE
+//  * Copyright (c) 2017-2018  Jeron A. Lau <jeron.lau@plopgrizzly.com>
+
+use std::fmt;
+
+use Vec3;
+use BCube;
+use Plane;
+use Frustum;
+
+/// Bounding sphere
+#[derive(Clone, Copy, PartialEq)]
+pub struct Sphere {
+	/// The center of the sphere
+	pub center: Vec3,
+	/// The radius of the sphere
+	pub radius: f32,
+}
+
+impl fmt::Debug for Sphere {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:?} r{:?}", self.center, self.radius)
+	}
+}
+
+impl Sphere {
+	/// Create a new `Sphere` at `center`.
+	pub fn new(center: Vec3, radius: f32) -> Sphere {
+		Sphere { center, radius }
+	}
+
+	/// Create the smallest `Sphere` that contains all of `cube`.
+	pub fn from_bcube(cube: BCube) -> Sphere {
+		Sphere::new(cube.center, cube.half_len * 3.0f32.sqrt())
+	}
+
+	/// Check if `Sphere` collides with `other` `Sphere` (touching counts).
+	pub fn intersects_sphere(self, other: Sphere) -> bool {
+		self.center.dist(other.center) <= self.radius + other.radius
+	}
+
+	/// Check if `Sphere` contains point `p` (including on the surface).
+	pub fn contains(self, p: Vec3) -> bool {
+		p.dist(self.center) <= self.radius
+	}
+
+	/// Check if `Sphere` touches or crosses `plane`.
+	pub fn intersects_plane(self, plane: Plane) -> bool {
+		plane.distance_to_point(self.center).abs() <= self.radius
+	}
+
+	/// Check if any part of the `Sphere` is inside `frustum`.
+	///
+	/// This may give false positives for spheres near the edges of the
+	/// frustum, which is fine for culling.
+	pub fn intersects_frustum(self, frustum: Frustum) -> bool {
+		for plane in frustum.extract_planes().iter() {
+			if plane.distance_to_point(self.center) < -self.radius {
+				return false;
+			}
+		}
+
+		true
+	}
+
+	/// Get the smallest `Sphere` that contains all of `points` (Welzl's
+	/// algorithm), or `None` if there are no points.
+	///
+	/// The points are shuffled first, so this runs in expected linear time
+	/// even if they're sorted (like most vertex buffers).
+	pub fn minimum_enclosing_sphere(points: &[Vec3]) -> Option<Sphere> {
+		let mut p = points.to_vec();
+		let mut sphere = Sphere::new(*p.first()?, 0.0);
+
+		shuffle(&mut p);
+		// Allow for rounding in the spheres made from boundary points.
+		let outside = |s: Sphere, p: Vec3| {
+			p.dist(s.center) > s.radius * (1.0 + 0.00001) + 0.000001
+		};
+
+		// Each point outside of the sphere so far must be on the
+		// boundary of the sphere containing it and the points before it.
+		for i in 1..p.len() {
+			if !outside(sphere, p[i]) { continue }
+			sphere = Sphere::new(p[i], 0.0);
+			for j in 0..i {
+				if !outside(sphere, p[j]) { continue }
+				sphere = sphere_2(p[i], p[j]);
+				for k in 0..j {
+					if !outside(sphere, p[k]) { continue }
+					sphere = sphere_3(p[i], p[j], p[k]);
+					for l in 0..k {
+						if !outside(sphere, p[l]) { continue }
+						sphere = sphere_4(p[i], p[j], p[k],
+							p[l]);
+					}
+				}
+			}
+		}
+
+		// Grow to fit the rounding, so that `contains()` is true.
+		for point in points {
+			sphere.radius = sphere.radius.max(point.dist(sphere.center));
+		}
+
+		Some(sphere)
+	}
+
+	/// Get the smallest `BCube` that contains all of the `Sphere`.
+	pub fn bounding_bcube(self) -> BCube {
+		BCube::from_sphere(self)
+	}
+}
+
+/// Shuffle `points` (Fisher-Yates).  The numbers only need to break up
+/// sorted input, so a fixed-seed xorshift is fine, and keeps the result the
+/// same every time.
+fn shuffle(points: &mut [Vec3]) {
+	let mut seed = 0x9E3779B9u32;
+
+	for i in (1..points.len()).rev() {
+		seed ^= seed << 13;
+		seed ^= seed >> 17;
+		seed ^= seed << 5;
+		points.swap(i, seed as usize % (i + 1));
+	}
+}
+
+/// Get the smallest `Sphere` with `a` and `b` on its surface.
+fn sphere_2(a: Vec3, b: Vec3) -> Sphere {
+	Sphere::new(a.midpoint(b), a.dist(b) / 2.0)
+}
+
+/// Get the smallest `Sphere` with `a`, `b` and `c` on its surface.
+fn sphere_3(a: Vec3, b: Vec3, c: Vec3) -> Sphere {
+	let ab = b - a;
+	let ac = c - a;
+	let n = ab.cross(ac);
+	let denom = 2.0 * n.dot(n);
+
+	// In a line - use the two farthest apart.  `n` is as long as `ab` and
+	// `ac` multiplied if they're perpendicular, so compare to that.
+	if denom <= ::std::f32::EPSILON * ab.dot(ab) * ac.dot(ac) {
+		return largest(&[sphere_2(a, b), sphere_2(a, c), sphere_2(b, c)]);
+	}
+
+	let offset = (n.cross(ab) * ac.dot(ac) + ac.cross(n) * ab.dot(ab))
+		/ denom;
+
+	Sphere::new(a + offset, offset.mag())
+}
+
+/// Get the `Sphere` with `a`, `b`, `c` and `d` on its surface.
+fn sphere_4(a: Vec3, b: Vec3, c: Vec3, d: Vec3) -> Sphere {
+	let u = b - a;
+	let v = c - a;
+	let w = d - a;
+	let denom = 2.0 * u.dot(v.cross(w));
+
+	// In a plane (allowing for a few rounding errors) - use the largest of
+	// the circles.  `denom` is twice the lengths of `u`, `v` and `w`
+	// multiplied if they're perpendicular, so compare to that.
+	let size = u.mag() * v.mag() * w.mag();
+	if denom.abs() <= 8.0 * ::std::f32::EPSILON * size {
+		return largest(&[sphere_3(a, b, c), sphere_3(a, b, d),
+			sphere_3(a, c, d), sphere_3(b, c, d)]);
+	}
+
+	let offset = (v.cross(w) * u.dot(u) + w.cross(u) * v.dot(v)
+		+ u.cross(v) * w.dot(w)) / denom;
+
+	Sphere::new(a + offset, offset.mag())
+}
+
+/// Get the `Sphere` with the largest radius.
+fn largest(spheres: &[Sphere]) -> Sphere {
+	let mut out = spheres[0];
+
+	for sphere in &spheres[1..] {
+		if sphere.radius > out.radius {
+			out = *sphere;
+		}
+	}
+
+	out
+}
+
+#[test]
+fn test_sphere_from_bcube() {
+	let cube = BCube { center: Vec3::new(1.0, -2.0, 3.0), half_len: 2.0 };
+	let sphere = cube.to_sphere();
+
+	for corner in cube.to_bbox().all_points().iter() {
+		assert!(corner.dist(sphere.center) <= sphere.radius + 0.0001);
+	}
+	assert!(sphere.bounding_bcube().contains(Vec3::new(1.0, -2.0, 4.9)));
+}
+
+#[test]
+fn test_sphere_intersects() {
+	let a = Sphere::new(Vec3::zero(), 1.0);
+	let b = Sphere::new(Vec3::new(3.0, 0.0, 0.0), 2.0);
+	let c = Sphere::new(Vec3::new(0.0, 3.5, 0.0), 2.0);
+	assert!(a.intersects_sphere(b));
+	assert!(b.intersects_sphere(a));
+	assert!(!a.intersects_sphere(c));
+	assert!(a.contains(Vec3::new(0.0, 0.0, -1.0)));
+	assert!(!a.contains(Vec3::new(0.0, 1.01, 0.0)));
+}
+
+#[test]
+fn test_sphere_intersects_plane() {
+	let plane = Plane::new(Vec3::new(0.0, 1.0, 0.0), 2.0);
+	// In front of, behind, straddling and touching the plane.
+	assert!(!Sphere::new(Vec3::new(0.0, 4.0, 0.0), 1.0).intersects_plane(plane));
+	assert!(!Sphere::new(Vec3::new(5.0, 0.0, 0.0), 1.0).intersects_plane(plane));
+	assert!(Sphere::new(Vec3::new(0.0, 2.5, 9.0), 1.0).intersects_plane(plane));
+	assert!(Sphere::new(Vec3::new(0.0, 1.0, 0.0), 1.0).intersects_plane(plane));
+
+	let pi = ::std::f32::consts::PI;
+	let frustum = Frustum::new(Vec3::zero(), 10.0, 0.0, 0.0, pi / 2.0,
+		pi / 2.0);
+	assert!(Sphere::new(Vec3::new(0.0, 0.0, 5.0), 1.0)
+		.intersects_frustum(frustum));
+	assert!(Sphere::new(Vec3::new(0.0, 0.0, -0.5), 1.0)
+		.intersects_frustum(frustum));
+	assert!(!Sphere::new(Vec3::new(0.0, 0.0, -2.0), 1.0)
+		.intersects_frustum(frustum));
+	assert!(!Sphere::new(Vec3::new(0.0, 0.0, 12.0), 1.0)
+		.intersects_frustum(frustum));
+}
+
+#[test]
+fn test_sphere_minimum_enclosing_sphere() {
+	assert!(Sphere::minimum_enclosing_sphere(&[]).is_none());
+	let p = Vec3::new(1.0, 2.0, 3.0);
+	assert!(Sphere::minimum_enclosing_sphere(&[p, p])
+		== Some(Sphere::new(p, 0.0)));
+
+	// Cube corners, with some points inside.
+	let cube = BCube { center: Vec3::new(1.0, -2.0, 3.0), half_len: 2.0 };
+	let mut points = vec![cube.center];
+	points.extend(cube.all_face_midpoints().iter());
+	points.extend(cube.all_corners().iter());
+	let sphere = Sphere::minimum_enclosing_sphere(&points).unwrap();
+	assert!(sphere.center.dist(cube.center) < 0.0001);
+	assert!((sphere.radius - cube.to_sphere().radius).abs() < 0.0001);
+
+	let mut rng = ::test_util::Lcg::new(0xDEADBEEF);
+	let mut rand = || rng.next_f32();
+	let points: Vec<Vec3> = (0..500)
+		.map(|_| Vec3::new(rand(), rand() * 0.5, rand()))
+		.collect();
+	let sphere = Sphere::minimum_enclosing_sphere(&points).unwrap();
+	assert!(points.iter().all(|p| sphere.contains(*p)));
+	// Minimal: moving the center any way makes it need to be bigger.
+	let max_dist = |c: Vec3| {
+		points.iter().map(|p| p.dist(c)).fold(0.0, f32::max)
+	};
+	for d in [Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0),
+		Vec3::new(0.0, 0.0, 1.0), Vec3::new(1.0, 1.0, 1.0)].iter()
+	{
+		assert!(max_dist(sphere.center + *d * 0.1) > sphere.radius);
+		assert!(max_dist(sphere.center - *d * 0.1) > sphere.radius);
+	}
+}
+
+#[test]
+fn test_sphere_minimum_enclosing_sphere_sorted() {
+	// Sorted points along a line: every point is outside of the sphere of
+	// the points before it, which is quadratic without shuffling.
+	let points: Vec<Vec3> = (0..100000)
+		.map(|i| Vec3::new(i as f32 * 0.01, 0.0, 0.0))
+		.collect();
+	let sphere = Sphere::minimum_enclosing_sphere(&points).unwrap();
+	assert!(sphere.center.dist(Vec3::new(499.995, 0.0, 0.0)) < 0.01);
+	assert!((sphere.radius - 499.995).abs() < 0.01);
+
+	// Sorted grid.
+	let mut points = vec![];
+	for x in 0..200 {
+		for y in 0..200 {
+			points.push(Vec3::new(x as f32, y as f32, 0.0));
+		}
+	}
+	let sphere = Sphere::minimum_enclosing_sphere(&points).unwrap();
+	assert!(sphere.center.dist(Vec3::new(99.5, 99.5, 0.0)) < 0.01);
+	assert!((sphere.radius - 99.5 * 2.0f32.sqrt()).abs() < 0.01);
+	assert!(points.iter().all(|p| sphere.contains(*p)));
+}
+
+#[test]
+fn test_sphere_minimum_enclosing_sphere_scale() {
+	let s3 = 3.0f32.sqrt();
+	// Triangle, tetrahedron, and points a little off of a line.
+	let triangle = [Vec3::new(1.0, 0.0, 0.0), Vec3::new(-0.5, s3 / 2.0, 0.0),
+		Vec3::new(-0.5, -s3 / 2.0, 0.0)];
+	let tetrahedron = [Vec3::new(1.0, 1.0, 1.0), Vec3::new(1.0, -1.0, -1.0),
+		Vec3::new(-1.0, 1.0, -1.0), Vec3::new(-1.0, -1.0, 1.0)];
+	let line = [Vec3::new(-1.0, 0.0, 0.0), Vec3::new(0.5, 0.0001, 0.0),
+		Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 0.0001)];
+
+	for scale in [0.001, 1.0, 1000.0].iter() {
+		for &(points, radius) in [(&triangle[..], 1.0), (&tetrahedron[..], s3),
+			(&line[..], 1.0)].iter()
+		{
+			let points: Vec<Vec3> = points.iter().map(|p| *p * *scale)
+				.collect();
+			let sphere = Sphere::minimum_enclosing_sphere(&points).unwrap();
+			assert!(sphere.center.mag() < 0.0001 * scale);
+			assert!((sphere.radius - radius * scale).abs() < 0.0001 * scale);
+		}
+	}
+}
+
+#[test]
+fn test_sphere_4_coplanar() {
+	// Points on a circle, on a tilted plane so that they're only coplanar
+	// up to rounding.
+	let center = Vec3::new(1.5, -0.9, 3.2);
+	let u = Vec3::new(1.0, 2.0, 3.0).normalize();
+	let v = u.cross(Vec3::new(-2.0, 0.5, 1.0)).normalize();
+	for scale in [0.001, 1000.0].iter() {
+		let p = |t: f32| center * *scale
+			+ (u * t.cos() + v * t.sin()) * *scale;
+		let sphere = sphere_4(p(0.1), p(1.7), p(3.0), p(4.4));
+		assert!((sphere.radius - scale).abs() < 0.001 * scale);
+	}
+}
@@ -0,0 +1,41 @@
+// "ami" crate - Licensed under the MIT LICENSE
+//  * Copyright (c) 2017-2018  Jeron A. Lau <jeron.lau@plopgrizzly.com>
+
+//! Helpers shared by the tests.
+
+/// Deterministic pseudo-random numbers (a linear congruential generator), so
+/// that tests with lots of inputs give the same results every run.
+pub struct Lcg(u32);
+
+impl Lcg {
+	/// Start a new sequence from `seed`.
+	pub fn new(seed: u32) -> Lcg {
+		Lcg(seed)
+	}
+
+	/// Get the next number in the sequence, from -50 to 50.
+	pub fn next_f32(&mut self) -> f32 {
+		self.0 = self.0.wrapping_mul(1664525).wrapping_add(1013904223);
+		(self.0 >> 8) as f32 / (1 << 24) as f32 * 100.0 - 50.0
+	}
+}
+
+/// Check that points from `sample` are spread evenly around `center`: 8000
+/// samples should put about 1000 in each octant.
+#[cfg(feature = "rand")]
+pub fn assert_even_octants<F>(center: ::Vec3, mut sample: F)
+	where F: FnMut() -> ::Vec3
+{
+	let mut octants = [0; 8];
+
+	for _ in 0..8000 {
+		let p = sample();
+		let i = (p.x < center.x) as usize * 4
+			+ (p.y < center.y) as usize * 2
+			+ (p.z < center.z) as usize;
+		octants[i] += 1;
+	}
+	for count in octants.iter() {
+		assert!(*count > 800 && *count < 1200);
+	}
+}
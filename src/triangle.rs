@@ -0,0 +1,272 @@
+// "ami" crate - Licensed under the MIT LICENSE
+//  * Copyright (c) 2017-2018  Jeron A. Lau <jeron.lau@plopgrizzly.com>
+
+use std::fmt;
+
+use Vec2;
+use Vec3;
+use Vec4;
+use Plane;
+
+/// A triangle made of 3 points.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Triangle {
+	/// The first vertex
+	pub a: Vec3,
+	/// The second vertex
+	pub b: Vec3,
+	/// The third vertex
+	pub c: Vec3,
+}
+
+/// Values that can be blended from 3 values with barycentric weights, used for
+/// `Triangle::interpolate_attributes()`.
+pub trait Lerp3 {
+	/// Get `a * u + b * v + c * w`.
+	fn lerp3(a: Self, b: Self, c: Self, u: f32, v: f32, w: f32) -> Self;
+}
+
+impl Lerp3 for f32 {
+	fn lerp3(a: f32, b: f32, c: f32, u: f32, v: f32, w: f32) -> f32 {
+		a * u + b * v + c * w
+	}
+}
+
+impl Lerp3 for Vec2 {
+	fn lerp3(a: Vec2, b: Vec2, c: Vec2, u: f32, v: f32, w: f32) -> Vec2 {
+		a * u + b * v + c * w
+	}
+}
+
+impl Lerp3 for Vec3 {
+	fn lerp3(a: Vec3, b: Vec3, c: Vec3, u: f32, v: f32, w: f32) -> Vec3 {
+		a * u + b * v + c * w
+	}
+}
+
+impl Lerp3 for Vec4 {
+	fn lerp3(a: Vec4, b: Vec4, c: Vec4, u: f32, v: f32, w: f32) -> Vec4 {
+		Vec4::new(
+			f32::lerp3(a.x, b.x, c.x, u, v, w),
+			f32::lerp3(a.y, b.y, c.y, u, v, w),
+			f32::lerp3(a.z, b.z, c.z, u, v, w),
+			f32::lerp3(a.w, b.w, c.w, u, v, w),
+		)
+	}
+}
+
+/// What is left of a `Triangle` after `Triangle::clip_to_plane()`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Clipped {
+	/// All of the triangle was clipped away.
+	Empty,
+	/// One triangle is left (either the whole triangle, or its tip).
+	One(Triangle),
+	/// Two triangles are left (the quadrilateral left by cutting off a
+	/// tip).
+	Two(Triangle, Triangle),
+}
+
+impl fmt::Debug for Triangle {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "△{:?}{:?}{:?}", self.a, self.b, self.c)
+	}
+}
+
+impl Triangle {
+	/// Create a new triangle from 3 vertices.
+	pub fn new(a: Vec3, b: Vec3, c: Vec3) -> Triangle {
+		Triangle { a, b, c }
+	}
+
+	/// Get the barycentric coordinates (weights of `a`, `b` and `c`) of
+	/// point `p` projected onto the triangle's plane.
+	pub fn barycentric(self, p: Vec3) -> (f32, f32, f32) {
+		let v0 = self.b - self.a;
+		let v1 = self.c - self.a;
+		let v2 = p - self.a;
+		let d00 = v0.dot(v0);
+		let d01 = v0.dot(v1);
+		let d11 = v1.dot(v1);
+		let d20 = v2.dot(v0);
+		let d21 = v2.dot(v1);
+		let denom = d00 * d11 - d01 * d01;
+		let v = (d11 * d20 - d01 * d21) / denom;
+		let w = (d00 * d21 - d01 * d20) / denom;
+
+		(1.0 - v - w, v, w)
+	}
+
+	/// Clip the triangle to the front (positive) side of `plane`.  The
+	/// triangles left have the same winding order as `self`.  Only area
+	/// strictly in front is kept: vertices on the plane are kept but never
+	/// split an edge, so no zero-area triangles are made, and a triangle
+	/// with nothing in front (including one lying in the plane) is
+	/// `Empty`.
+	pub fn clip_to_plane(self, plane: Plane) -> Clipped {
+		let v = [self.a, self.b, self.c];
+		let d = [
+			plane.distance_to_point(self.a),
+			plane.distance_to_point(self.b),
+			plane.distance_to_point(self.c),
+		];
+
+		if d.iter().all(|d| *d <= 0.0) {
+			return Clipped::Empty;
+		} else if d.iter().all(|d| *d >= 0.0) {
+			return Clipped::One(self);
+		}
+
+		// Walk around the edges, keeping the vertices that aren't behind
+		// and adding a point where an edge goes across the plane.
+		let mut p = [Vec3::zero(); 4];
+		let mut n = 0;
+		for i in 0..3 {
+			let j = (i + 1) % 3;
+
+			if d[i] >= 0.0 {
+				p[n] = v[i];
+				n += 1;
+			}
+			if (d[i] > 0.0 && d[j] < 0.0) || (d[i] < 0.0 && d[j] > 0.0) {
+				p[n] = v[i] + (v[j] - v[i]) * (d[i] / (d[i] - d[j]));
+				n += 1;
+			}
+		}
+
+		if n == 3 {
+			Clipped::One(Triangle::new(p[0], p[1], p[2]))
+		} else {
+			Clipped::Two(Triangle::new(p[0], p[1], p[2]),
+				Triangle::new(p[0], p[2], p[3]))
+		}
+	}
+
+	/// Blend the attributes (UVs, normals, colors...) of vertices `a`, `b`
+	/// and `c` at the barycentric coordinates `bary`, as returned by
+	/// `barycentric()`.
+	pub fn interpolate_attributes<T: Lerp3>(self, attrs: [T; 3],
+		bary: (f32, f32, f32)) -> T
+	{
+		let [a, b, c] = attrs;
+
+		T::lerp3(a, b, c, bary.0, bary.1, bary.2)
+	}
+
+	/// Check if point `p`, projected onto the triangle's plane, is inside
+	/// the triangle (including the edges).  Always false for a degenerate
+	/// triangle.
+	pub fn contains_point_projected(self, p: Vec3) -> bool {
+		let (u, v, w) = self.barycentric(p);
+		let within = |x: f32| x >= 0.0 && x <= 1.0;
+
+		within(u) && within(v) && within(w)
+	}
+}
+
+#[test]
+fn test_triangle_contains_point_projected() {
+	let t = Triangle::new(Vec3::zero(), Vec3::new(4.0, 0.0, 0.0),
+		Vec3::new(0.0, 4.0, 0.0));
+
+	assert!(t.contains_point_projected(t.a));
+	assert!(t.contains_point_projected(t.b));
+	assert!(t.contains_point_projected(t.c));
+	assert!(t.contains_point_projected(t.a.midpoint(t.b)));
+	assert!(t.contains_point_projected(t.b.midpoint(t.c)));
+	assert!(t.contains_point_projected(t.c.midpoint(t.a)));
+	assert!(t.contains_point_projected((t.a + t.b + t.c) / 3.0));
+	// Above the centroid, off the plane.
+	assert!(t.contains_point_projected(Vec3::new(1.0, 1.0, 7.0)));
+	assert!(!t.contains_point_projected(Vec3::new(3.0, 3.0, 0.0)));
+	assert!(!t.contains_point_projected(Vec3::new(-0.1, 1.0, 0.0)));
+}
+
+#[test]
+fn test_triangle_clip_to_plane() {
+	let t = Triangle::new(Vec3::zero(), Vec3::new(4.0, 0.0, 0.0),
+		Vec3::new(0.0, 4.0, 0.0));
+	let normal = |t: Triangle| (t.b - t.a).cross(t.c - t.a);
+	let area = |t: Triangle| normal(t).mag() / 2.0;
+	let clip = |x: f32, facing: f32| {
+		t.clip_to_plane(Plane::new(Vec3::new(facing, 0.0, 0.0), x * facing))
+	};
+
+	// All in front, and all behind
+	assert!(clip(-1.0, 1.0) == Clipped::One(t));
+	assert!(clip(5.0, 1.0) == Clipped::Empty);
+	// Only b in front (x >= 2)
+	match clip(2.0, 1.0) {
+		Clipped::One(a) => {
+			assert!((area(a) - 2.0).abs() < 0.0001);
+			assert!(normal(a).z > 0.0);
+			assert!(a.a.x >= 2.0 && a.b.x >= 2.0 && a.c.x >= 2.0);
+		}
+		_ => panic!("Expected one triangle"),
+	}
+	// Only b behind (x <= 2)
+	match clip(2.0, -1.0) {
+		Clipped::Two(a, b) => {
+			assert!((area(a) + area(b) - 6.0).abs() < 0.0001);
+			assert!(normal(a).z > 0.0 && normal(b).z > 0.0);
+			for p in [a.a, a.b, a.c, b.a, b.b, b.c].iter() {
+				assert!(p.x <= 2.0);
+			}
+		}
+		_ => panic!("Expected two triangles"),
+	}
+}
+
+#[test]
+fn test_triangle_clip_to_plane_on_plane() {
+	let t = Triangle::new(Vec3::zero(), Vec3::new(4.0, 0.0, 0.0),
+		Vec3::new(0.0, 4.0, 0.0));
+	let normal = |t: Triangle| (t.b - t.a).cross(t.c - t.a);
+	let clip = |x: f32, facing: f32| {
+		t.clip_to_plane(Plane::new(Vec3::new(facing, 0.0, 0.0), x * facing))
+	};
+	// Through `a` and the middle of b-c: one triangle each way, made of
+	// `a`, the cut point and the vertex in front.
+	let diagonal = Plane::new(Vec3::new(1.0, -1.0, 0.0), 0.0);
+	let cut = Vec3::new(2.0, 2.0, 0.0);
+	for &(plane, tip) in [(diagonal, t.b), (diagonal.flip(), t.c)].iter() {
+		match t.clip_to_plane(plane) {
+			Clipped::One(a) => {
+				assert!(normal(a).z > 0.0);
+				for p in [t.a, tip].iter() {
+					assert!(*p == a.a || *p == a.b || *p == a.c);
+				}
+				assert!((a.a + a.b + a.c - t.a - tip).dist(cut) < 0.0001);
+			}
+			_ => panic!("Expected one triangle"),
+		}
+	}
+
+	// Only the vertex `b` on the plane, with the rest behind or in front.
+	assert!(clip(4.0, 1.0) == Clipped::Empty);
+	assert!(clip(4.0, -1.0) == Clipped::One(t));
+	// The edge a-c on the plane, with `b` in front or behind.
+	assert!(clip(0.0, 1.0) == Clipped::One(t));
+	assert!(clip(0.0, -1.0) == Clipped::Empty);
+	// Lying in the plane.
+	let z = Plane::new(Vec3::new(0.0, 0.0, 1.0), 0.0);
+	assert!(t.clip_to_plane(z) == Clipped::Empty);
+}
+
+#[test]
+fn test_triangle_interpolate_attributes() {
+	let t = Triangle::new(Vec3::zero(), Vec3::new(4.0, 0.0, 0.0),
+		Vec3::new(0.0, 4.0, 0.0));
+	let uvs = [Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0)];
+	let colors = [Vec4::new(1.0, 0.0, 0.0, 1.0), Vec4::new(0.0, 1.0, 0.0, 1.0),
+		Vec4::new(0.0, 0.0, 1.0, 0.0)];
+
+	let bary = t.barycentric(Vec3::new(1.0, 2.0, 0.0));
+	assert!(t.interpolate_attributes(uvs, bary) == Vec2::new(0.25, 0.5));
+	assert!(t.interpolate_attributes(colors, bary)
+		== Vec4::new(0.25, 0.25, 0.5, 0.5));
+	assert!(t.interpolate_attributes([t.a, t.b, t.c], bary)
+		== Vec3::new(1.0, 2.0, 0.0));
+	assert!(t.interpolate_attributes([1.0, 2.0, 3.0], t.barycentric(t.c))
+		== 3.0);
+}
@@ -22,6 +22,12 @@ impl fmt::Debug for Vec3 {
 	}
 }
 
+impl fmt::Display for Vec3 {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "({}, {}, {})", self.x, self.y, self.z)
+	}
+}
+
 impl ops::Add for Vec3 {
 	type Output = Vec3;
 
@@ -123,11 +129,58 @@ impl Vec3 {
 		Self::new(x, y, z)
 	}
 
+	/// Get the translation of `mat`, which is the X, Y and Z of its last
+	/// column (`mat.0[12]`, `mat.0[13]` and `mat.0[14]`).  Same as
+	/// `mat.extract_translation()`.
+	pub fn from_mat4_translation(mat: ::Mat4) -> Self {
+		mat.extract_translation()
+	}
+
 	/// Create a zero Vec3
 	pub fn zero() -> Self {
 		Vec3::new(0.0, 0.0, 0.0)
 	}
 
+	/// Create a Vec3 with all components set to 1
+	pub fn one() -> Self {
+		Vec3::splat(1.0)
+	}
+
+	/// Create a Vec3 with all components set to `v`
+	pub fn splat(v: f32) -> Self {
+		Vec3::new(v, v, v)
+	}
+
+	/// Create a unit Vec3 pointing along +X
+	pub fn x_axis() -> Self {
+		Vec3::new(1.0, 0.0, 0.0)
+	}
+
+	/// Create a unit Vec3 pointing along +Y
+	pub fn y_axis() -> Self {
+		Vec3::new(0.0, 1.0, 0.0)
+	}
+
+	/// Create a unit Vec3 pointing along +Z
+	pub fn z_axis() -> Self {
+		Vec3::new(0.0, 0.0, 1.0)
+	}
+
+	/// Create a unit Vec3 pointing along -X
+	pub fn neg_x_axis() -> Self {
+		Vec3::new(-1.0, 0.0, 0.0)
+	}
+
+	/// Create a unit Vec3 pointing along -Y
+	pub fn neg_y_axis() -> Self {
+		Vec3::new(0.0, -1.0, 0.0)
+	}
+
+	/// Create a unit Vec3 pointing along -Z
+	pub fn neg_z_axis() -> Self {
+		Vec3::new(0.0, 0.0, -1.0)
+	}
+
 	/// Find the midpoint between two Vec3
 	pub fn midpoint(self, other: Self) -> Self {
 		let x = (self.x + other.x) / 2.0;
@@ -136,6 +189,24 @@ impl Vec3 {
 		Vec3::new(x, y, z)
 	}
 
+	/// Find the average of `points`, each `(point, weight)`.  Returns `None`
+	/// if there are no points or the weights add up to 0.
+	pub fn weighted_average(points: &[(Vec3, f32)]) -> Option<Vec3> {
+		let mut sum = Vec3::zero();
+		let mut total = 0.0;
+
+		for &(point, weight) in points {
+			sum += point * weight;
+			total += weight;
+		}
+
+		if total == 0.0 {
+			None
+		} else {
+			Some(sum / total)
+		}
+	}
+
 	/// Calculate the distance squared between two Vec3
 	pub fn dist_sq(self, other: Self) -> f32 {
 		let dx = other.x - self.x;
@@ -144,6 +215,11 @@ impl Vec3 {
 		dx * dx + dy * dy + dz * dz
 	}
 
+	/// Calculate the distance between two Vec3
+	pub fn dist(self, other: Self) -> f32 {
+		self.dist_sq(other).sqrt()
+	}
+
 	/// The recipricol (inverse) of the vector.
 	pub fn recip(self) -> Self {
 		Vec3::new(1.0 / self.x, 1.0 / self.y, 1.0 / self.z)
@@ -154,6 +230,61 @@ impl Vec3 {
 		self.x * other.x + self.y * other.y + self.z * other.z
 	}
 
+	/// Calculate the cross product of two `Vec3`s
+	pub fn cross(&self, other: Vec3) -> Vec3 {
+		Vec3::new(
+			self.y * other.z - self.z * other.y,
+			self.z * other.x - self.x * other.z,
+			self.x * other.y - self.y * other.x,
+		)
+	}
+
+	/// Rotate around `axis` by `angle` radians (counter-clockwise when
+	/// `axis` points at you), using Rodrigues' rotation formula.
+	pub fn rotate_around_axis(self, axis: Vec3, angle: f32) -> Vec3 {
+		let axis = axis.normalize();
+		let (sin, cos) = (angle.sin(), angle.cos());
+
+		self * cos + axis.cross(self) * sin
+			+ axis * (axis.dot(self) * (1.0 - cos))
+	}
+
+	/// Rotate around the X axis by `angle` radians (Y towards Z).
+	pub fn rotate_x(self, angle: f32) -> Vec3 {
+		let (sin, cos) = (angle.sin(), angle.cos());
+
+		Vec3::new(self.x, self.y * cos - self.z * sin,
+			self.y * sin + self.z * cos)
+	}
+
+	/// Rotate around the Y axis by `angle` radians (Z towards X).
+	pub fn rotate_y(self, angle: f32) -> Vec3 {
+		let (sin, cos) = (angle.sin(), angle.cos());
+
+		Vec3::new(self.z * sin + self.x * cos, self.y,
+			self.z * cos - self.x * sin)
+	}
+
+	/// Rotate around the Z axis by `angle` radians (X towards Y).
+	pub fn rotate_z(self, angle: f32) -> Vec3 {
+		let (sin, cos) = (angle.sin(), angle.cos());
+
+		Vec3::new(self.x * cos - self.y * sin,
+			self.x * sin + self.y * cos, self.z)
+	}
+
+	/// Calculate the outer product of two `Vec3`s (the matrix where row `i`
+	/// column `j` is `self[i] * rhs[j]`).
+	pub fn outer_product(self, rhs: Vec3) -> ::Mat3 {
+		let (a, b) = (self, rhs);
+
+		::Mat3([
+			a.x * b.x, a.y * b.x, a.z * b.x,
+			a.x * b.y, a.y * b.y, a.z * b.y,
+			a.x * b.z, a.y * b.z, a.z * b.z,
+		])
+	}
+
 	/// Normalize a Vec3
 	pub fn normalize(self) -> Self {
 		let m = self.mag();
@@ -164,7 +295,196 @@ impl Vec3 {
 		}
 	}
 
-	/// Calculate angle between 2 Vec3's
+	/// Normalize a Vec3, or get `fallback` if it's too short to have a
+	/// reliable direction (magnitude under `f32::EPSILON`).
+	pub fn normalize_or(self, fallback: Vec3) -> Self {
+		let m = self.mag();
+		if m >= ::std::f32::EPSILON {
+			self / m
+		} else {
+			fallback
+		}
+	}
+
+	/// Get 1 divided by the magnitude of a Vec3 (infinity for a zero
+	/// length Vec3).
+	pub fn length_recip(self) -> f32 {
+		1.0 / self.mag()
+	}
+
+	/// Iterate over the X, Y and Z components.
+	pub fn iter(&self) -> impl Iterator<Item = &f32> {
+		use std::iter::once;
+
+		once(&self.x).chain(once(&self.y)).chain(once(&self.z))
+	}
+
+	/// Iterate over the X, Y and Z components (mutable).
+	pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut f32> {
+		use std::iter::once;
+
+		once(&mut self.x).chain(once(&mut self.y)).chain(once(&mut self.z))
+	}
+
+	/// Apply a function to each component.
+	pub fn map(self, f: impl Fn(f32) -> f32) -> Vec3 {
+		Vec3::new(f(self.x), f(self.y), f(self.z))
+	}
+
+	/// Apply a function to each pair of components from two `Vec3`s.
+	pub fn zip(self, other: Vec3, f: impl Fn(f32, f32) -> f32) -> Vec3 {
+		Vec3::new(f(self.x, other.x), f(self.y, other.y), f(self.z, other.z))
+	}
+
+	/// Round each component to the nearest multiple of `grid`.  Halfway
+	/// values round away from zero.  Panics if `grid` isn't positive.
+	pub fn snap(self, grid: f32) -> Vec3 {
+		assert!(grid > 0.0, "grid size must be positive, not {}", grid);
+
+		self.map(|x| (x / grid).round() * grid)
+	}
+
+	/// Calculate linear interpolation of two Vec3 (`t` = 0 is `self`, `t`
+	/// = 1 is `other`).
+	pub fn lerp(self, other: Vec3, t: f32) -> Vec3 {
+		self.zip(other, |a, b| mix_f32(a, b, t))
+	}
+
+	/// Same as `lerp()`, named after the GLSL function.
+	pub fn mix(self, other: Vec3, t: f32) -> Vec3 {
+		self.lerp(other, t)
+	}
+
+	/// Clamp each component between 0 and 1.
+	pub fn saturate(self) -> Vec3 {
+		self.map(saturate_f32)
+	}
+
+	/// Create a Vec3 from cylindrical coordinates around the Z axis.
+	///
+	/// * `r` - Distance from the Z axis.
+	/// * `theta` - Angle (radians) around the Z axis, from +X towards +Y.
+	/// * `z` - Height along the Z axis.
+	pub fn from_cylindrical(r: f32, theta: f32, z: f32) -> Vec3 {
+		Vec3::new(r * theta.cos(), r * theta.sin(), z)
+	}
+
+	/// Convert to cylindrical coordinates `(r, theta, z)`, the opposite of
+	/// `from_cylindrical()`.  `theta` is from -PI to PI.
+	pub fn to_cylindrical(self) -> (f32, f32, f32) {
+		(self.x.hypot(self.y), self.y.atan2(self.x), self.z)
+	}
+
+	/// Create a Vec3 from spherical coordinates.
+	///
+	/// * `r` - Distance from the origin.
+	/// * `theta` - Angle (radians) around the Z axis, from +X towards +Y.
+	/// * `phi` - Angle (radians) down from +Z.
+	pub fn from_spherical(r: f32, theta: f32, phi: f32) -> Vec3 {
+		Vec3::from_cylindrical(r * phi.sin(), theta, r * phi.cos())
+	}
+
+	/// Convert to spherical coordinates `(r, theta, phi)`, the opposite of
+	/// `from_spherical()`.  `theta` is from -PI to PI, and `phi` from 0 to
+	/// PI.
+	pub fn to_spherical(self) -> (f32, f32, f32) {
+		let r = self.mag();
+		let phi = if r == 0.0 { 0.0 } else { (self.z / r).acos() };
+
+		(r, self.y.atan2(self.x), phi)
+	}
+
+	/// Get the largest component.
+	pub fn max_component(self) -> f32 {
+		self.x.max(self.y).max(self.z)
+	}
+
+	/// Get the smallest component.
+	pub fn min_component(self) -> f32 {
+		self.x.min(self.y).min(self.z)
+	}
+
+	/// Get the axis (0 = X, 1 = Y, 2 = Z) of the component farthest from
+	/// zero.  Ties go to the earlier axis.
+	pub fn dominant_axis(self) -> usize {
+		let (x, y, z) = (self.x.abs(), self.y.abs(), self.z.abs());
+
+		if x >= y && x >= z {
+			0
+		} else if y >= z {
+			1
+		} else {
+			2
+		}
+	}
+
+	/// Get the fractional part of each component, with the same sign as
+	/// the component (like `f32::fract()`).
+	pub fn fract(self) -> Vec3 {
+		self.map(f32::fract)
+	}
+
+	/// Get the integer part of each component, rounding towards zero.
+	pub fn trunc(self) -> Vec3 {
+		self.map(f32::trunc)
+	}
+
+	/// For each component, get 0 if `x` is less than `edge`, otherwise 1.
+	/// Named after the GLSL function.
+	pub fn step(edge: Vec3, x: Vec3) -> Vec3 {
+		edge.zip(x, step_f32)
+	}
+
+	/// Move `current` towards `target` with a critically damped spring, so
+	/// that it slows down as it gets there without overshooting.
+	///
+	/// * `velocity` - The current velocity, updated for the next call.
+	/// * `smooth_time` - About how long it takes to reach `target`.
+	/// * `delta_time` - Time since the last call.
+	pub fn smooth_damp(current: Vec3, target: Vec3, velocity: &mut Vec3,
+		smooth_time: f32, delta_time: f32) -> Vec3
+	{
+		let omega = 2.0 / smooth_time.max(0.0001);
+		let x = omega * delta_time;
+		// Approximation of e^-x
+		let exp = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+		let change = current - target;
+		let temp = (*velocity + change * omega) * delta_time;
+
+		*velocity = (*velocity - temp * omega) * exp;
+
+		let output = target + (change + temp) * exp;
+
+		// Don't overshoot
+		if (target - current).dot(output - target) > 0.0 {
+			*velocity = Vec3::zero();
+			return target;
+		}
+
+		output
+	}
+
+	/// Calculate the angle (radians, 0 to PI) between 2 Vec3's.  Same as
+	/// `angle()`.
+	pub fn angle_to(self, other: Vec3) -> f32 {
+		self.angle(other)
+	}
+
+	/// Calculate the signed angle (radians, -PI to PI) from `self` to
+	/// `other`, positive when it's counter-clockwise around `axis` (with
+	/// `axis` pointing at you).  NaN if either is zero length.
+	pub fn signed_angle_to(self, other: Vec3, axis: Vec3) -> f32 {
+		let angle = self.angle_to(other);
+
+		if axis.dot(self.cross(other)) < 0.0 {
+			-angle
+		} else {
+			angle
+		}
+	}
+
+	/// Calculate angle (radians, 0 to PI) between 2 Vec3's.  NaN if either
+	/// is zero length.
 	pub fn angle(&self, other: Vec3) -> f32 {
 		let mag1 = (self.x as f64)
 			.hypot(self.y as f64)
@@ -176,6 +496,309 @@ impl Vec3 {
 			+ ((self.y as f64) * (other.y as f64))
 			+ ((self.z as f64) * (other.z as f64));
 
-		(dot / (mag1 * mag2)).acos() as f32
+		let mag = mag1 * mag2;
+
+		if mag == 0.0 {
+			return ::std::f32::NAN;
+		}
+
+		// Clamp, because rounding can make it a little past -1 or 1.
+		(dot / mag).max(-1.0).min(1.0).acos() as f32
 	}
 }
+
+/// Orthonormalize three vectors with the Gram-Schmidt process.  The direction
+/// of `u` is kept, `v` is made perpendicular to `u` and `w` to both.
+pub fn gram_schmidt(u: Vec3, v: Vec3, w: Vec3) -> (Vec3, Vec3, Vec3) {
+	let u = u.normalize();
+	let v = (v - u * v.dot(u)).normalize();
+	let w = (w - u * w.dot(u) - v * w.dot(v)).normalize();
+
+	(u, v, w)
+}
+
+/// Calculate linear interpolation of two values, named after the GLSL
+/// function (`t` = 0 is `a`, `t` = 1 is `b`).
+pub fn mix_f32(a: f32, b: f32, t: f32) -> f32 {
+	a + (b - a) * t
+}
+
+/// Get 0 if `x` is less than `edge`, otherwise 1.  Named after the GLSL
+/// function.
+pub fn step_f32(edge: f32, x: f32) -> f32 {
+	if x < edge { 0.0 } else { 1.0 }
+}
+
+/// Clamp `x` between 0 and 1.
+pub fn saturate_f32(x: f32) -> f32 {
+	x.max(0.0).min(1.0)
+}
+
+/// Transform a world space position into normalized device coordinates, with
+/// the homogeneous divide.
+pub fn project(p: Vec3, view_proj: ::Mat4) -> Vec3 {
+	let clip = view_proj * ::Vec4::new(p.x, p.y, p.z, 1.0);
+	Vec3::new(clip.x, clip.y, clip.z) / clip.w
+}
+
+/// Transform normalized device coordinates back into a world space position,
+/// with the homogeneous divide.  This is the opposite of `project()`.
+pub fn unproject(ndc: Vec3, inverse_view_proj: ::Mat4) -> Vec3 {
+	project(ndc, inverse_view_proj)
+}
+
+#[test]
+fn test_vec3_display() {
+	assert_eq!(format!("{}", Vec3::new(1.0, -2.5, 0.0)), "(1, -2.5, 0)");
+}
+
+#[test]
+fn test_gram_schmidt() {
+	let (u, v, w) = gram_schmidt(Vec3::new(2.0, 0.0, 0.0),
+		Vec3::new(1.0, 1.0, 0.0), Vec3::new(0.3, 0.4, 5.0));
+	assert!(u == Vec3::new(1.0, 0.0, 0.0));
+	assert!(v == Vec3::new(0.0, 1.0, 0.0));
+	assert!(w == Vec3::new(0.0, 0.0, 1.0));
+
+	let (u, v, w) = gram_schmidt(Vec3::new(1.0, 2.0, 3.0),
+		Vec3::new(-1.0, 0.5, 2.0), Vec3::new(4.0, -2.0, 1.0));
+	assert!(u.dot(v).abs() < 0.0001);
+	assert!(u.dot(w).abs() < 0.0001);
+	assert!(v.dot(w).abs() < 0.0001);
+	assert!((w.mag() - 1.0).abs() < 0.0001);
+	assert!(u.dist(Vec3::new(1.0, 2.0, 3.0).normalize()) < 0.0001);
+}
+
+#[test]
+fn test_unproject() {
+	let eye = Vec3::new(1.0, 2.0, 5.0);
+	let view = ::Mat4::look_at_rh(eye, Vec3::zero(), Vec3::y_axis());
+	let proj = ::Mat4::perspective_rh(::std::f32::consts::PI / 3.0, 1.5,
+		0.5, 50.0);
+	let vp = view * proj;
+	let inv = vp.inverse().unwrap();
+
+	for ndc in [Vec3::zero(), Vec3::new(1.0, -1.0, 0.5),
+		Vec3::new(-0.25, 0.75, -1.0)].iter()
+	{
+		let world = unproject(*ndc, inv);
+		assert!(project(world, vp).dist(*ndc) < 0.0001);
+	}
+	for world in [Vec3::zero(), Vec3::new(3.0, -1.0, -10.0)].iter() {
+		assert!(unproject(project(*world, vp), inv).dist(*world) < 0.001);
+	}
+
+	// Farther along the same line of sight: same X and Y, deeper Z.
+	let near = project(Vec3::zero(), vp);
+	let far = project(eye * -2.0, vp);
+	assert!((near.x - far.x).abs() < 0.0001);
+	assert!((near.y - far.y).abs() < 0.0001);
+	assert!(far.z > near.z);
+}
+
+#[test]
+fn test_vec3_iter() {
+	let mut a = Vec3::new(1.5, -2.0, 3.0);
+	assert!(a.iter().sum::<f32>() == 2.5);
+	for i in a.iter_mut() {
+		*i *= 2.0;
+	}
+	assert!(a == Vec3::new(3.0, -4.0, 6.0));
+	assert!(a.map(f32::abs) == Vec3::new(3.0, 4.0, 6.0));
+	assert!(a.zip(Vec3::new(1.0, 1.0, 7.0), f32::max)
+		== Vec3::new(3.0, 1.0, 7.0));
+}
+
+#[test]
+fn test_vec3_snap() {
+	assert!(Vec3::new(0.5, -1.0, 2.0).snap(0.5) == Vec3::new(0.5, -1.0, 2.0));
+	assert!(Vec3::new(0.2, 0.3, -0.74).snap(0.5) == Vec3::new(0.0, 0.5, -0.5));
+	assert!(Vec3::new(0.25, -0.25, 0.75).snap(0.5)
+		== Vec3::new(0.5, -0.5, 1.0));
+}
+
+#[test]
+fn test_vec3_saturate() {
+	assert!(Vec3::new(-0.5, 0.25, 1.5).saturate() == Vec3::new(0.0, 0.25, 1.0));
+	assert!(saturate_f32(1.0) == 1.0 && saturate_f32(0.0) == 0.0);
+}
+
+#[test]
+fn test_vec3_step() {
+	let edge = Vec3::new(1.0, -2.0, 0.5);
+	assert!(Vec3::step(edge, Vec3::new(0.0, -3.0, 0.49)) == Vec3::zero());
+	assert!(Vec3::step(edge, edge) == Vec3::one());
+	assert!(Vec3::step(edge, Vec3::new(2.0, -2.0, 0.0))
+		== Vec3::new(1.0, 1.0, 0.0));
+	assert!(step_f32(0.0, -0.1) == 0.0 && step_f32(0.0, 0.0) == 1.0);
+}
+
+#[test]
+fn test_vec3_cylindrical_spherical() {
+	let pi = ::std::f32::consts::PI;
+	let close = |a: Vec3, b: Vec3| a.dist(b) < 0.0001;
+
+	assert!(close(Vec3::from_cylindrical(2.0, pi / 2.0, 3.0),
+		Vec3::new(0.0, 2.0, 3.0)));
+	assert!(close(Vec3::from_spherical(2.0, pi, pi / 2.0),
+		Vec3::new(-2.0, 0.0, 0.0)));
+	assert!(close(Vec3::from_spherical(2.0, 1.0, 0.0),
+		Vec3::new(0.0, 0.0, 2.0)));
+
+	for v in [Vec3::new(1.0, 2.0, 3.0), Vec3::new(-4.0, 0.5, -1.0),
+		Vec3::new(0.0, -3.0, 0.0)].iter()
+	{
+		let (r, theta, z) = v.to_cylindrical();
+		assert!(close(Vec3::from_cylindrical(r, theta, z), *v));
+		let (r, theta, phi) = v.to_spherical();
+		assert!(close(Vec3::from_spherical(r, theta, phi), *v));
+	}
+	assert!(Vec3::zero().to_spherical() == (0.0, 0.0, 0.0));
+}
+
+#[test]
+fn test_vec3_components() {
+	let v = Vec3::new(2.0, -5.0, 3.0);
+	assert!(v.max_component() == 3.0 && v.min_component() == -5.0);
+	assert!(Vec3::new(4.0, -1.0, 2.0).dominant_axis() == 0);
+	assert!(v.dominant_axis() == 1);
+	assert!(Vec3::new(0.5, 0.0, -0.75).dominant_axis() == 2);
+	assert!(Vec3::one().dominant_axis() == 0);
+}
+
+#[test]
+fn test_vec3_fract_trunc() {
+	let v = Vec3::new(1.25, -2.75, 3.0);
+	assert!(v.trunc() == Vec3::new(1.0, -2.0, 3.0));
+	assert!(v.fract() == Vec3::new(0.25, -0.75, 0.0));
+	assert!(v.fract() + v.trunc() == v);
+}
+
+#[test]
+fn test_vec3_rotate_around_axis() {
+	let half_pi = ::std::f32::consts::PI / 2.0;
+	let x = Vec3::new(1.0, 0.0, 0.0);
+	let z = Vec3::new(0.0, 0.0, 2.0);
+	assert!(x.rotate_around_axis(z, half_pi).dist(Vec3::new(0.0, 1.0, 0.0))
+		< 0.0001);
+	// Same as rotating with a Quaternion
+	let axis = Vec3::new(1.0, -2.0, 0.5);
+	let v = Vec3::new(3.0, 1.0, -1.0);
+	let q = ::Quaternion::from_axis_angle(axis, 0.7);
+	assert!(v.rotate_around_axis(axis, 0.7).dist(q * v) < 0.0001);
+}
+
+#[test]
+fn test_vec3_from_mat4_translation() {
+	let m = ::IDENTITY.rotate(0.25, 0.5, 0.1).translate(1.0, 2.0, 3.0);
+	assert!(Vec3::from_mat4_translation(m) == Vec3::new(1.0, 2.0, 3.0));
+	assert!(Vec3::from_mat4_translation(m) == m * Vec3::zero());
+}
+
+#[test]
+fn test_vec3_rotate_xyz() {
+	let half_pi = ::std::f32::consts::PI / 2.0;
+	let (x, y, z) = (Vec3::x_axis(), Vec3::y_axis(), Vec3::z_axis());
+	assert!(y.rotate_x(half_pi).dist(z) < 0.0001);
+	assert!(z.rotate_y(half_pi).dist(x) < 0.0001);
+	assert!(x.rotate_z(half_pi).dist(y) < 0.0001);
+	// Same as rotating around the axis.
+	let v = Vec3::new(3.0, 1.0, -1.0);
+	assert!(v.rotate_x(0.7).dist(v.rotate_around_axis(x, 0.7)) < 0.0001);
+	assert!(v.rotate_y(0.7).dist(v.rotate_around_axis(y, 0.7)) < 0.0001);
+	assert!(v.rotate_z(0.7).dist(v.rotate_around_axis(z, 0.7)) < 0.0001);
+}
+
+#[test]
+fn test_vec3_midpoint() {
+	let a = Vec3::new(1.0, 2.0, -4.0);
+	let b = Vec3::new(3.0, -2.0, 0.0);
+	assert!(a.midpoint(b) == b.midpoint(a));
+	assert!(a.midpoint(b) == a.lerp(b, 0.5));
+
+	assert!(Vec3::weighted_average(&[]).is_none());
+	assert!(Vec3::weighted_average(&[(a, 1.0), (b, -1.0)]).is_none());
+	assert!(Vec3::weighted_average(&[(a, 2.0), (b, 2.0)])
+		== Some(a.midpoint(b)));
+	assert!(Vec3::weighted_average(&[(a, 3.0), (b, 1.0)])
+		== Some(a.lerp(b, 0.25)));
+}
+
+#[test]
+fn test_vec3_normalize_or() {
+	let up = Vec3::new(0.0, 1.0, 0.0);
+	assert!(Vec3::zero().normalize_or(up) == up);
+	assert!(Vec3::new(1e-9, 0.0, -1e-9).normalize_or(up) == up);
+	assert!(Vec3::new(0.0, 0.0, -4.0).normalize_or(up)
+		== Vec3::new(0.0, 0.0, -1.0));
+
+	assert!(Vec3::new(0.0, 3.0, 4.0).length_recip() == 0.2);
+	assert!(Vec3::zero().length_recip() == ::std::f32::INFINITY);
+}
+
+#[test]
+fn test_vec3_lerp() {
+	let a = Vec3::new(1.0, 2.0, -4.0);
+	let b = Vec3::new(3.0, -2.0, 0.0);
+	assert!(a.lerp(b, 0.0) == a);
+	assert!(a.lerp(b, 1.0) == b);
+	assert!(a.lerp(b, 0.25) == Vec3::new(1.5, 1.0, -3.0));
+	assert!(a.mix(b, 0.25) == a.lerp(b, 0.25));
+	assert!(mix_f32(2.0, 4.0, 0.5) == 3.0);
+}
+
+#[test]
+fn test_vec3_outer_product() {
+	let x = Vec3::new(1.0, 0.0, 0.0);
+	assert!(x.outer_product(x)
+		== ::Mat3([1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]));
+	// (a ⊗ b) * v = a * (b · v)
+	let a = Vec3::new(1.0, 2.0, 3.0);
+	let b = Vec3::new(-1.0, 0.5, 2.0);
+	let v = Vec3::new(4.0, 2.0, 1.0);
+	assert!(a.outer_product(b) * v == a * b.dot(v));
+}
+
+#[test]
+fn test_vec3_smooth_damp() {
+	let target = Vec3::new(10.0, -5.0, 2.0);
+	let mut current = Vec3::zero();
+	let mut velocity = Vec3::zero();
+
+	for _ in 0..300 {
+		let next = Vec3::smooth_damp(current, target, &mut velocity, 0.5,
+			1.0 / 60.0);
+		// Always getting closer
+		assert!(next.dist(target) <= current.dist(target));
+		current = next;
+	}
+
+	assert!(current.dist(target) < 0.001);
+	assert!(velocity.mag() < 0.01);
+}
+
+#[test]
+fn test_vec3_angle_to() {
+	let pi = ::std::f32::consts::PI;
+	let a = Vec3::new(1.0, 2.0, 3.0);
+	assert!(a.angle_to(a) == 0.0);
+	assert!(a.angle_to(a * 0.5) == 0.0);
+	assert!(a.angle_to(-a) == pi);
+	assert!((a.angle_to(Vec3::new(3.0, 0.0, -1.0)) - pi / 2.0).abs() < 0.0001);
+	// Zero length has no direction.
+	assert!(Vec3::zero().angle(a).is_nan());
+	assert!(a.angle_to(Vec3::zero()).is_nan());
+	assert!(Vec3::zero().signed_angle_to(a, Vec3::z_axis()).is_nan());
+}
+
+#[test]
+fn test_vec3_signed_angle_to() {
+	let pi = ::std::f32::consts::PI;
+	let x = Vec3::new(1.0, 0.0, 0.0);
+	let y = Vec3::new(0.0, 1.0, 0.0);
+	let z = Vec3::new(0.0, 0.0, 1.0);
+	assert!((x.signed_angle_to(y, z) - pi / 2.0).abs() < 0.0001);
+	assert!((y.signed_angle_to(x, z) + pi / 2.0).abs() < 0.0001);
+	assert!((x.signed_angle_to(y, -z) + pi / 2.0).abs() < 0.0001);
+	assert!(x.signed_angle_to(-x, z) == pi);
+}
@@ -23,6 +23,12 @@ impl fmt::Debug for Vec4 {
 	}
 }
 
+impl fmt::Display for Vec4 {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "({}, {}, {}, {})", self.x, self.y, self.z, self.w)
+	}
+}
+
 #[allow(unused)]
 impl Vec4 {
 	/// Create a new Vec4
@@ -30,6 +36,56 @@ impl Vec4 {
 		Vec4 { x, y, z, w }
 	}
 
+	/// Create a Vec4 with all components set to 1
+	pub fn one() -> Vec4 {
+		Vec4::splat(1.0)
+	}
+
+	/// Create a Vec4 with all components set to `v`
+	pub fn splat(v: f32) -> Vec4 {
+		Vec4::new(v, v, v, v)
+	}
+
+	/// Unpack a color in the form `0xRRGGBBAA` into (r, g, b, a), each from
+	/// 0 to 1.
+	pub fn from_rgba_u32(rgba: u32) -> Vec4 {
+		::Color::from_u32(rgba).to_vec4()
+	}
+
+	/// Pack a color (r, g, b, a) into the form `0xRRGGBBAA`.  Components
+	/// are clamped to 0-1.
+	pub fn to_rgba_u32(self) -> u32 {
+		::Color::from_vec4(self).to_u32()
+	}
+
+	/// Unpack a color in the form `0xAARRGGBB` into (r, g, b, a), each from
+	/// 0 to 1.
+	pub fn from_argb_u32(argb: u32) -> Vec4 {
+		Vec4::from_rgba_u32(argb.rotate_left(8))
+	}
+
+	/// Pack a color (r, g, b, a) into the form `0xAARRGGBB`.  Components
+	/// are clamped to 0-1.
+	pub fn to_argb_u32(self) -> u32 {
+		self.to_rgba_u32().rotate_right(8)
+	}
+
+	/// Calculate linear interpolation of two Vec4 (`t` = 0 is `self`, `t`
+	/// = 1 is `other`).
+	pub fn lerp(self, other: Vec4, t: f32) -> Vec4 {
+		Vec4::new(
+			::mix_f32(self.x, other.x, t),
+			::mix_f32(self.y, other.y, t),
+			::mix_f32(self.z, other.z, t),
+			::mix_f32(self.w, other.w, t),
+		)
+	}
+
+	/// Same as `lerp()`, named after the GLSL function.
+	pub fn mix(self, other: Vec4, t: f32) -> Vec4 {
+		self.lerp(other, t)
+	}
+
 	/// Find the minimum ordinal value
 	pub(crate) fn min_p(self) -> f32 {
 		self.x.min(self.y).min(self.z).min(self.w)
@@ -40,3 +96,29 @@ impl Vec4 {
 		self.x.max(self.y).max(self.z).max(self.w)
 	}
 }
+
+#[test]
+fn test_vec4_display() {
+	assert_eq!(format!("{}", Vec4::new(1.0, -2.5, 0.0, 0.25)),
+		"(1, -2.5, 0, 0.25)");
+}
+
+#[test]
+fn test_vec4_lerp() {
+	let a = Vec4::new(1.0, 2.0, -4.0, 0.0);
+	let b = Vec4::new(3.0, -2.0, 0.0, 1.0);
+	assert!(a.lerp(b, 0.0) == a);
+	assert!(a.lerp(b, 1.0) == b);
+	assert!(a.lerp(b, 0.5) == Vec4::new(2.0, 0.0, -2.0, 0.5));
+	assert!(a.mix(b, 0.25) == a.lerp(b, 0.25));
+}
+
+#[test]
+fn test_vec4_color_u32() {
+	for x in [0x00000000u32, 0xFFFFFFFF, 0xFF8000CC, 0x12345678].iter() {
+		assert!(Vec4::from_rgba_u32(*x).to_rgba_u32() == *x);
+		assert!(Vec4::from_argb_u32(*x).to_argb_u32() == *x);
+	}
+	assert!(Vec4::from_rgba_u32(0xFF000080).to_argb_u32() == 0x80FF0000);
+	assert!(Vec4::from_argb_u32(0xFF00FF00) == Vec4::new(0.0, 1.0, 0.0, 1.0));
+}